@@ -0,0 +1,154 @@
+use std::alloc::{GlobalAlloc, Layout};
+
+use arena::{Arena, ArenaAllocator, ArenaChunk, ArenaGlobal};
+use arena::single_chunk::SingleArena;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+const ALLOCATIONS: usize = 10_000;
+
+#[allow(dead_code)]
+struct LargeStruct {
+    integers: [u64; 32],
+    string: String
+}
+
+impl Default for LargeStruct {
+    fn default() -> Self {
+        Self { integers: [7; 32], string: String::from("large struct") }
+    }
+}
+
+fn small_allocations(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small_u8");
+    group.throughput(Throughput::Elements(ALLOCATIONS as u64));
+
+    group.bench_function("single_arena", |b| b.iter_batched(
+        || SingleArena::new(ALLOCATIONS).unwrap(),
+        |arena| {
+            for i in 0..ALLOCATIONS {
+                std::mem::forget(black_box(arena.allocate(i as u8).unwrap()));
+            }
+        },
+        BatchSize::SmallInput
+    ));
+    group.bench_function("arena", |b| b.iter(|| {
+        let arena = Arena::new();
+        for i in 0..ALLOCATIONS {
+            black_box(arena.allocate(i as u8));
+        }
+    }));
+    group.bench_function("box", |b| b.iter(|| {
+        for i in 0..ALLOCATIONS {
+            black_box(Box::new(i as u8));
+        }
+    }));
+    group.finish();
+}
+
+fn full_chunk(c: &mut Criterion) {
+    let mut group = c.benchmark_group("full_chunk");
+    group.throughput(Throughput::Elements(ALLOCATIONS as u64));
+
+    // every allocation fails, as happens before an arena grows
+    group.bench_function("single_arena", |b| {
+        let arena = SingleArena::new(8).unwrap();
+        let _full = arena.allocate(0u64).unwrap();
+        b.iter(|| {
+            for i in 0..ALLOCATIONS {
+                black_box(arena.allocate(black_box(i as u32)));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn mixed_alignment(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mixed_alignment");
+    group.throughput(Throughput::Elements(3 * ALLOCATIONS as u64));
+
+    group.bench_function("arena", |b| b.iter(|| {
+        let arena = Arena::new();
+        let mut stored = Vec::with_capacity(ALLOCATIONS);
+        for i in 0..ALLOCATIONS {
+            stored.push((arena.allocate(i as u8), arena.allocate(i as u64), arena.allocate(i as u16)));
+        }
+        black_box(stored);
+    }));
+    group.bench_function("box", |b| b.iter(|| {
+        let mut stored = Vec::with_capacity(ALLOCATIONS);
+        for i in 0..ALLOCATIONS {
+            stored.push((Box::new(i as u8), Box::new(i as u64), Box::new(i as u16)));
+        }
+        black_box(stored);
+    }));
+    group.finish();
+}
+
+fn large_structs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("large_struct");
+    group.throughput(Throughput::Elements(ALLOCATIONS as u64));
+
+    group.bench_function("single_arena", |b| b.iter_batched(
+        || SingleArena::new(ALLOCATIONS * std::mem::size_of::<LargeStruct>()).unwrap(),
+        |arena| {
+            let mut stored = Vec::with_capacity(ALLOCATIONS);
+            for _i in 0..ALLOCATIONS {
+                stored.push(arena.allocate_with(LargeStruct::default).unwrap());
+            }
+            black_box(stored);
+        },
+        BatchSize::LargeInput
+    ));
+    group.bench_function("arena", |b| b.iter(|| {
+        let arena = Arena::new();
+        let mut stored = Vec::with_capacity(ALLOCATIONS);
+        for _i in 0..ALLOCATIONS {
+            stored.push(arena.allocate(LargeStruct::default()));
+        }
+        black_box(stored);
+    }));
+    group.bench_function("box", |b| b.iter(|| {
+        let mut stored = Vec::with_capacity(ALLOCATIONS);
+        for _i in 0..ALLOCATIONS {
+            stored.push(Box::new(LargeStruct::default()));
+        }
+        black_box(stored);
+    }));
+    group.finish();
+}
+
+fn multithreaded(c: &mut Criterion) {
+    const THREADS: usize = 4;
+    let layout = Layout::new::<u64>();
+
+    let mut group = c.benchmark_group("multithreaded_u64");
+    group.throughput(Throughput::Elements((THREADS * ALLOCATIONS) as u64));
+
+    // ArenaGlobal is the thread safe allocator, shared by the threads with an atomic offset
+    group.bench_function("arena_global", |b| b.iter_batched(
+        Box::<ArenaGlobal<{ THREADS * ALLOCATIONS * 8 }>>::default,
+        |arena| std::thread::scope(|scope| {
+            for _thread in 0..THREADS {
+                scope.spawn(|| {
+                    for _i in 0..ALLOCATIONS {
+                        black_box(unsafe { arena.alloc(layout) });
+                    }
+                });
+            }
+        }),
+        BatchSize::LargeInput
+    ));
+    group.bench_function("box", |b| b.iter(|| std::thread::scope(|scope| {
+        for _thread in 0..THREADS {
+            scope.spawn(|| {
+                for i in 0..ALLOCATIONS {
+                    black_box(Box::new(i as u64));
+                }
+            });
+        }
+    })));
+    group.finish();
+}
+
+criterion_group!(benches, small_allocations, full_chunk, mixed_alignment, large_structs, multithreaded);
+criterion_main!(benches);
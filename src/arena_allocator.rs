@@ -1,55 +1,647 @@
-use crate::single_chunk::SingleArena;
+use crate::single_chunk::{SingleArena, BumpDirection, Marker};
 
 use super::ArenaChunk;
 use super::ArenaAllocator;
+use super::AllocateIn;
 use super::ArenaBox;
 use super::chunk_linked_list::UnshrinkableLinkedList;
 
-use std::mem::size_of;
+use std::mem::{size_of, align_of};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 
-const CHUNK_SIZE: usize = 4096;
+pub(crate) const CHUNK_SIZE: usize = 4096;
 
+/// An arena made of a list of [`SingleArena`] chunks, which creates a new chunk when the last chunk is full.
+/// 
+/// # Thread safety
+/// The chunks are stored in an `UnsafeCell`, and the chunks themselves aren't thread safe,
+/// so `Arena` is neither `Send` nor `Sync`.
+/// 
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<arena::Arena>();
+/// ```
+/// 
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<arena::Arena>();
+/// ```
 pub struct Arena {
-    pub(crate) chunks: UnshrinkableLinkedList<SingleArena>
+    pub(crate) chunks: UnshrinkableLinkedList<SingleArena>,
+    // chunks which will be used before creating new chunks, with the next chunk at the end
+    spare_chunks: RefCell<Vec<SingleArena>>,
+    // chunks are taken from and returned to the pool, if there is one
+    pool: Option<ArenaPool>,
+    pub(crate) growth_policy: GrowthPolicy,
+    // the minimum size of new chunks
+    pub(crate) chunk_size: usize,
+    // settings for new chunks
+    pub(crate) zeroizing: bool,
+    pub(crate) poisoning: bool,
+    // whether to search all chunks for capacity, instead of only the last chunk
+    pub(crate) first_fit: bool,
+    // the maximum total size of the chunks
+    pub(crate) max_total_bytes: usize,
+    // called with the size and alignment of each allocation, shared with child arenas
+    on_allocate: Option<Rc<dyn Fn(usize, usize)>>,
+    // the number of child arenas which haven't been dropped, which may reference the last chunk
+    children: Cell<usize>
+}
+
+/// How the size of each new chunk of an [`Arena`] is chosen.
+/// 
+/// Each new chunk is `factor` times the size of the last chunk, capped at `max_chunk_size`,
+/// and at least the arena's chunk size, which is 4096 bytes by default.
+/// It is always large enough for the allocation that needed it.
+/// 
+/// The default factor of 1 gives chunks of a constant size.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GrowthPolicy {
+    pub factor: f64,
+    pub max_chunk_size: usize
+}
+
+impl Default for GrowthPolicy {
+    fn default() -> Self {
+        Self { factor: 1.0, max_chunk_size: usize::MAX }
+    }
+}
+
+/// The reason that a new chunk couldn't be added to an [`Arena`].
+enum NewChunkError {
+    /// The chunk would make the arena larger than its maximum total size.
+    ExceedsMaximum,
+    /// The chunk would be larger than isize::MAX.
+    TooLarge,
+    /// Allocating a chunk of this size failed.
+    AllocationFailed(usize)
+}
+
+/// The state of every chunk of an [`Arena`], which it can be restored to with [`Arena::restore`].
+#[derive(Clone, Debug)]
+pub struct ArenaSnapshot {
+    // a marker for each chunk which existed when the snapshot was made
+    markers: Vec<Marker>
+}
+
+struct PoolInner {
+    chunks: RefCell<Vec<SingleArena>>,
+    max_chunks: usize,
+    chunks_created: Cell<usize>
+}
+
+/// A pool of freed chunks, which arenas created with [`Arena::new_in`] take chunks from,
+/// and return their chunks to when dropped, instead of deallocating them.
+/// 
+/// This avoids allocating on the heap when arenas are frequently created and dropped.
+/// The pool holds at most `max_chunks` chunks, and any further chunks are deallocated.
+/// 
+/// Cloning the pool returns another handle to the same pool.
+#[derive(Clone)]
+pub struct ArenaPool {
+    inner: Rc<PoolInner>
+}
+
+impl ArenaPool {
+    pub fn new(max_chunks: usize) -> Self {
+        Self { inner: Rc::new(PoolInner { chunks: RefCell::new(Vec::new()), max_chunks, chunks_created: Cell::new(0) }) }
+    }
+
+    /// The number of chunks currently held in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.chunks.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of chunks that arenas using this pool have allocated on the heap, because the pool had no chunk large enough.
+    pub fn chunks_created(&self) -> usize {
+        self.inner.chunks_created.get()
+    }
+
+    /// Remove a chunk from the pool with between `min_size` and `max_size` bytes, or create a new one of `new_size` bytes.
+    /// 
+    /// Return None if creating the chunk fails.
+    fn take(&self, min_size: usize, max_size: usize, new_size: usize) -> Option<SingleArena> {
+        let mut chunks = self.inner.chunks.borrow_mut();
+        if let Some(index) = chunks.iter().rposition(|chunk| (min_size..=max_size).contains(&chunk.size())) {
+            return Some(chunks.swap_remove(index))
+        }
+
+        let chunk = SingleArena::try_new(new_size)?;
+        self.inner.chunks_created.set(self.inner.chunks_created.get() + 1);
+        Some(chunk)
+    }
+
+    /// Reset a chunk and return it to the pool, or deallocate it if the pool is full.
+    fn give(&self, mut chunk: SingleArena) {
+        let mut chunks = self.inner.chunks.borrow_mut();
+        // chunks with leaked allocations aren't reused, as the memory may still be referenced
+        if chunks.len() < self.inner.max_chunks && chunk.allocations.get() == 0 && chunk.direction() == BumpDirection::Upward {
+            chunk.reset();
+            chunks.push(chunk);
+        }
+    }
 }
 
 impl Arena {
-    /// # Safety
-    /// UB if the constant CHUNK_SIZE is 0 and min_size is 0 (not very likely)
-    unsafe fn new_chunk(&self, min_size: usize) {
-        let chunk = SingleArena::new_unchecked(std::cmp::max(min_size, CHUNK_SIZE));
+    /// Create an empty arena, which doesn't allocate until the first allocation.
+    /// 
+    /// This is a `const fn`, so an arena can be a `const`, or a thread local initialised with `const`.
+    /// `Arena` isn't `Sync`, so it can't be a `static`.
+    /// 
+    /// ```
+    /// use arena::{Arena, ArenaAllocator};
+    /// 
+    /// thread_local! {
+    ///     static ARENA: Arena = const { Arena::new() };
+    /// }
+    /// ARENA.with(|arena| assert_eq!(*arena.allocate(5), 5));
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            chunks: UnshrinkableLinkedList::new(),
+            spare_chunks: RefCell::new(Vec::new()),
+            pool: None,
+            growth_policy: GrowthPolicy { factor: 1.0, max_chunk_size: usize::MAX },
+            chunk_size: CHUNK_SIZE,
+            zeroizing: false,
+            poisoning: cfg!(feature = "poison"),
+            first_fit: false,
+            max_total_bytes: usize::MAX,
+            on_allocate: None,
+            children: Cell::new(0)
+        }
+    }
+
+    /// Create an arena from existing chunks, such as to preallocate chunks of chosen sizes.
+    /// 
+    /// Allocations are made in the last chunk, consistent with `new`. The first chunk is used first,
+    /// and each following chunk is used once the previous chunk is full, before any new chunks are created.
+    /// 
+    /// # Panics
+    /// If any of the chunks allocate downward.
+    pub fn from_chunks(chunks: impl IntoIterator<Item = SingleArena>) -> Self {
+        let mut spare_chunks: Vec<SingleArena> = chunks.into_iter().collect();
+        assert!(
+            spare_chunks.iter().all(|chunk| chunk.direction() == BumpDirection::Upward),
+            "Arena chunks must allocate upward"
+        );
+        spare_chunks.reverse();
+
+        let arena = Self::new();
+        *arena.spare_chunks.borrow_mut() = spare_chunks;
+        if let Some(chunk) = arena.spare_chunks.borrow_mut().pop() {
+            arena.chunks.push(chunk);
+        }
+        arena
+    }
+
+    /// Add a chunk with at least `min_size` bytes of capacity, aborting if allocating it fails.
+    /// 
+    /// # Panics
+    /// If the chunk would make the arena larger than its maximum total size, or the chunk is larger than isize::MAX.
+    fn new_chunk(&self, min_size: usize) {
+        match self.try_new_chunk(min_size) {
+            Ok(()) => (),
+            Err(NewChunkError::ExceedsMaximum) => panic!("Arena would exceed its maximum total size of {} bytes", self.max_total_bytes),
+            Err(NewChunkError::TooLarge) => panic!("Chunk size is greater than isize::MAX"),
+            // safety: the size was checked to be a valid layout
+            Err(NewChunkError::AllocationFailed(size)) => std::alloc::handle_alloc_error(unsafe { std::alloc::Layout::from_size_align_unchecked(size, 1) })
+        }
+    }
+
+    /// Add a chunk with at least `min_size` bytes of capacity.
+    fn try_new_chunk(&self, min_size: usize) -> Result<(), NewChunkError> {
+        // use the next spare chunk if it has the capacity
+        let mut spare_chunks = self.spare_chunks.borrow_mut();
+        if spare_chunks.last().is_some_and(|chunk| chunk.remaining_capacity() >= min_size) {
+            self.chunks.push(spare_chunks.pop().unwrap());
+            return Ok(())
+        }
+
+        let grown_size = match self.chunks.last() {
+            // float to int casts saturate
+            Some(chunk) => ((chunk.size() as f64 * self.growth_policy.factor) as usize).min(self.growth_policy.max_chunk_size),
+            None => self.chunk_size
+        };
+        let grown_size = std::cmp::max(grown_size, self.chunk_size);
+
+        // spare chunks are counted, as they are already allocated
+        let total_capacity = self.chunks.iter().chain(spare_chunks.iter()).map(|chunk| chunk.size()).sum::<usize>();
+        let limit = self.max_total_bytes.checked_sub(total_capacity).ok_or(NewChunkError::ExceedsMaximum)?;
+        if min_size > limit {
+            return Err(NewChunkError::ExceedsMaximum)
+        }
+        if min_size > isize::MAX as usize {
+            return Err(NewChunkError::TooLarge)
+        }
+        // the chunk is made smaller to fit in the limit, and in a valid layout
+        let new_size = std::cmp::max(min_size, grown_size).min(limit).min(isize::MAX as usize);
+
+        let chunk = match &self.pool {
+            // chunks from the pool larger than the limit aren't used
+            Some(pool) => pool.take(min_size, limit, new_size),
+            None => SingleArena::try_new(new_size)
+        };
+        let mut chunk = chunk.ok_or(NewChunkError::AllocationFailed(new_size))?;
+        self.configure_chunk(&mut chunk);
         self.chunks.push(chunk);
+        Ok(())
+    }
+
+    /// Allocate an object in the arena, like `allocate`, but return the object if allocating a new chunk on the heap fails,
+    /// or it would make the arena larger than its maximum total size, instead of aborting the process.
+    pub fn try_allocate<T>(&self, object: T) -> Result<ArenaBox<'_, T, SingleArena>, T> {
+        let allocation_size = size_of::<T>();
+
+        if allocation_size == 0 || self.chunk_with_capacity(allocation_size, align_of::<T>()).is_some() {
+            return Ok(self.allocate(object))
+        }
+
+        if self.try_new_chunk(padded_size::<T>()).is_err() {
+            return Err(object)
+        }
+        self.notify_allocate::<T>();
+        let chunk = self.chunks.last().unwrap();
+        // safety: the new chunk has the capacity for the object
+        Ok(unsafe { chunk.allocate_unchecked(object) })
+    }
+
+    /// Return a chunk with the capacity for `size` bytes aligned to `align`, including the alignment padding, if there is one.
+    fn chunk_with_capacity(&self, size: usize, align: usize) -> Option<&SingleArena> {
+        let last = self.chunks.last()?;
+        if fits(last, size, align) {
+            return Some(last)
+        }
+        if self.first_fit {
+            return self.chunks.iter().find(|chunk| fits(chunk, size, align))
+        }
+        None
+    }
+
+    /// Create a child arena, which allocates in this arena's last chunk until it is full, then in chunks of its own.
+    /// 
+    /// The child borrows this arena, so it can't outlive it, and allocations in the child can't outlive the child.
+    /// When the child is dropped, its own chunks are given to this arena as spare chunks, to be used once this arena's chunks are full.
+    /// Memory that the child used in this arena's chunk is freed when this arena is reset.
+    /// 
+    /// The child uses this arena's settings, pool and allocation hook. Its chunks are limited to the capacity left
+    /// under this arena's maximum total size when the child is created.
+    pub fn child(&self) -> ChildArena<'_> {
+        let mut own = Self::new();
+        own.chunk_size = self.chunk_size;
+        own.growth_policy = self.growth_policy;
+        own.zeroizing = self.zeroizing;
+        own.poisoning = self.poisoning;
+        own.max_total_bytes = self.max_total_bytes.saturating_sub(self.total_capacity());
+        own.pool = self.pool.clone();
+        own.on_allocate = self.on_allocate.clone();
+        self.children.set(self.children.get() + 1);
+        ChildArena { parent: self, parent_chunk: self.chunks.last(), exhausted: Cell::new(false), own }
+    }
+
+    /// Set whether allocations that don't fit in the last chunk are made in the first chunk with enough capacity, before creating a new chunk.
+    /// 
+    /// This reuses space left in earlier chunks, such as after a large allocation spilled into a new chunk,
+    /// but makes each allocation that doesn't fit in the last chunk cost O(chunks), instead of O(1). It is off by default.
+    pub fn set_first_fit(&mut self, first_fit: bool) {
+        self.first_fit = first_fit;
+    }
+
+    /// Set a hook which is called with the size and alignment of each allocation, such as to log or histogram allocation sizes.
+    /// 
+    /// There is no hook by default, so allocations only check that there isn't one.
+    pub fn on_allocate(&mut self, f: impl Fn(usize, usize) + 'static) {
+        self.on_allocate = Some(Rc::new(f));
+    }
+
+    /// Call the allocation hook, if there is one, for an allocation of a T.
+    #[inline]
+    fn notify_allocate<T>(&self) {
+        if let Some(on_allocate) = &self.on_allocate {
+            on_allocate(size_of::<T>(), align_of::<T>())
+        }
+    }
+
+    /// Apply the arena's settings for new chunks to a chunk.
+    pub(crate) fn configure_chunk(&self, chunk: &mut SingleArena) {
+        chunk.set_zeroizing(self.zeroizing);
+        chunk.set_poisoning(self.poisoning);
+    }
+
+    /// Create an empty arena which sizes its chunks with a growth policy.
+    pub fn with_growth_policy(growth_policy: GrowthPolicy) -> Self {
+        let mut arena = Self::new();
+        arena.set_growth_policy(growth_policy);
+        arena
+    }
+
+    /// Change the growth policy used to size new chunks.
+    pub fn set_growth_policy(&mut self, growth_policy: GrowthPolicy) {
+        self.growth_policy = growth_policy;
+    }
+
+    /// Create an empty arena, which takes its chunks from `pool` and returns them to it when dropped.
+    pub fn new_in(pool: &ArenaPool) -> Self {
+        let mut arena = Self::new();
+        arena.pool = Some(pool.clone());
+        arena
+    }
+
+    /// Free all the allocations in the arena, keeping its chunks to be reused.
+    /// 
+    /// The destructors of objects that were leaked are NOT run.
+    pub fn reset(&mut self) {
+        let spare_chunks = self.spare_chunks.get_mut();
+        for mut chunk in self.chunks.take().into_iter().rev() {
+            chunk.reset();
+            spare_chunks.push(chunk);
+        }
+
+        if let Some(chunk) = spare_chunks.pop() {
+            self.chunks.push(chunk);
+        }
+    }
+
+    /// Move the chunks of `other` into this arena, such as to combine the results of two allocation passes.
+    /// 
+    /// `other` is consumed, so none of its allocations are borrowed. Leaked allocations in its chunks stay valid,
+    /// as the chunks are kept alive by this arena. New allocations are made in the last chunk of `other`.
+    /// 
+    /// `other` is returned if its chunks would make this arena larger than its maximum total size.
+    // the arena is returned by value, like the object from `try_allocate`
+    #[allow(clippy::result_large_err)]
+    pub fn absorb(&mut self, mut other: Arena) -> Result<(), Arena> {
+        if self.total_capacity().checked_add(other.total_capacity()).is_none_or(|total| total > self.max_total_bytes) {
+            return Err(other)
+        }
+
+        self.chunks.append(&mut other.chunks.take());
+        self.spare_chunks.get_mut().append(other.spare_chunks.get_mut());
+        Ok(())
+    }
+
+    /// Drop the last chunk if it has no allocations, such as when it is mostly empty after a workload, to reclaim its memory.
+    /// 
+    /// The chunk is returned to the arena's pool if it has one. Return whether the chunk was removed.
+    pub fn trim_last_chunk(&mut self) -> bool {
+        if self.chunks.last().is_none_or(|chunk| chunk.allocations.get() != 0) {
+            return false
+        }
+
+        let chunk = self.chunks.pop().unwrap();
+        if let Some(pool) = &self.pool {
+            pool.give(chunk);
+        }
+        true
+    }
+
+    /// Record the free pointer of every chunk, and the number of chunks, to roll back speculative allocations with [`restore`](Arena::restore).
+    pub fn snapshot(&self) -> ArenaSnapshot {
+        ArenaSnapshot { markers: self.chunks.iter().map(|chunk| chunk.mark()).collect() }
+    }
+
+    /// Restore the arena to a snapshot, freeing everything allocated since the snapshot was made.
+    /// 
+    /// Every chunk is rewound with [`SingleArena::rewind`], and chunks used since the snapshot are reset and kept as spare chunks,
+    /// to be used again in the same order before any new chunks are created. The destructors of the freed objects are NOT run.
+    /// 
+    /// # Safety
+    /// The snapshot must have been made by this arena, which mustn't have been reset, trimmed or absorbed into since.
+    /// No allocations made after the snapshot may be used again, including dropping their `ArenaBox`es, as they are dangling.
+    /// 
+    /// # Panics
+    /// If the arena has fewer chunks than when the snapshot was made, or it has a [`ChildArena`] which hasn't been dropped,
+    /// as the child may reference a chunk which would be removed.
+    pub unsafe fn restore(&self, snapshot: ArenaSnapshot) {
+        assert!(self.chunks.len() >= snapshot.markers.len(), "Arena has fewer chunks than when the snapshot was made");
+        assert_eq!(self.children.get(), 0, "Arena restored while it has child arenas");
+
+        // safety: the caller guarantees that allocations in the new chunks, which borrow them, aren't used
+        let new_chunks = self.chunks.split_off_shared(snapshot.markers.len());
+        let mut spare_chunks = self.spare_chunks.borrow_mut();
+        // spare chunks are used from the end, so the first removed chunk is used next
+        for mut chunk in new_chunks.into_iter().rev() {
+            // the chunk's allocations are dangling, so aren't leaks
+            chunk.reset();
+            spare_chunks.push(chunk);
+        }
+
+        for (chunk, marker) in self.chunks.iter().zip(snapshot.markers) {
+            chunk.rewind(marker);
+        }
+    }
+
+    /// Limit the total size of the arena's chunks, including spare chunks, to `max_total_bytes`, such as to bound memory usage.
+    /// 
+    /// Once a new chunk would exceed the limit, [`try_allocate`](Arena::try_allocate) fails, and `allocate` panics.
+    /// The last chunk is made smaller than the chunk size if that allows it to fit in the limit. There is no limit by default.
+    pub fn set_max_total_bytes(&mut self, max_total_bytes: usize) {
+        self.max_total_bytes = max_total_bytes;
+    }
+
+    /// The total size of the arena's chunks in bytes, including spare chunks that haven't been used yet.
+    pub fn total_capacity(&self) -> usize {
+        self.chunks.iter().chain(self.spare_chunks.borrow().iter()).map(|chunk| chunk.size()).sum()
+    }
+
+    /// The number of chunks that the arena is allocating in, not including spare chunks that haven't been used yet.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Whether allocations have spilled into more than one chunk, which means that the chunk size was too small for the arena's usage.
+    pub fn grew(&self) -> bool {
+        self.chunk_count() > 1
+    }
+
+    /// The total number of allocations in all the chunks of the arena, which can be used to detect leaks.
+    /// 
+    /// Zero sized types aren't allocated in a chunk, so aren't counted.
+    pub fn allocation_count(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.allocations.get()).sum()
+    }
+
+    /// Return a snapshot of `(chunk_index, used_bytes, allocation_count)` for each chunk, for debugging leaks.
+    #[cfg(feature = "debug_dump")]
+    pub fn debug_dump(&self) -> Vec<(usize, usize, usize)> {
+        self.chunks.iter()
+            .enumerate()
+            .map(|(i, chunk)| (i, chunk.used_capacity(), chunk.allocations.get()))
+            .collect()
+    }
+}
+
+/// The size of a chunk which can fit a T wherever the chunk's memory starts, as chunks are only aligned to 1 byte.
+fn padded_size<T>() -> usize {
+    // saturates, so that a chunk too large to allocate is requested
+    size_of::<T>().saturating_add(align_of::<T>() - 1)
+}
+
+/// Whether a chunk has the capacity for `size` bytes aligned to `align`, including the alignment padding.
+fn fits(chunk: &SingleArena, size: usize, align: usize) -> bool {
+    let offset = chunk.get_free_pointer_mut().align_offset(align);
+    size.checked_add(offset).is_some_and(|size| size <= chunk.remaining_capacity())
+}
+
+/// An arena created by [`Arena::child`], which shares the last chunk of its parent until the chunk is full, then grows independently.
+pub struct ChildArena<'p> {
+    parent: &'p Arena,
+    // the parent's last chunk when the child was created
+    parent_chunk: Option<&'p SingleArena>,
+    // set once an allocation didn't fit in the parent's chunk, so that allocations are only made in the child's chunks afterwards
+    exhausted: Cell<bool>,
+    own: Arena
+}
+
+impl<'p> ChildArena<'p> {
+    /// Allocate an object in the parent's chunk if it has the capacity, otherwise in the child's own chunks.
+    pub fn allocate<T>(&self, object: T) -> ArenaBox<'_, T, SingleArena> {
+        match self.parent_chunk_with_capacity::<T>() {
+            // safety: the chunk has the capacity for the object
+            Some(chunk) if size_of::<T>() != 0 => {
+                self.parent.notify_allocate::<T>();
+                unsafe { chunk.allocate_unchecked(object) }
+            },
+            _ => self.own.allocate(object)
+        }
+    }
+
+    /// Allocate an object like `allocate`, but return the object if allocating a new chunk on the heap fails,
+    /// instead of aborting the process.
+    pub fn try_allocate<T>(&self, object: T) -> Result<ArenaBox<'_, T, SingleArena>, T> {
+        match self.parent_chunk_with_capacity::<T>() {
+            // safety: the chunk has the capacity for the object
+            Some(chunk) if size_of::<T>() != 0 => {
+                self.parent.notify_allocate::<T>();
+                Ok(unsafe { chunk.allocate_unchecked(object) })
+            },
+            _ => self.own.try_allocate(object)
+        }
+    }
+
+    /// The number of chunks that the child has created, not including the parent's chunk.
+    pub fn chunk_count(&self) -> usize {
+        self.own.chunk_count()
+    }
+
+    /// Return the parent's chunk, if the child is still allocating in it and it has the capacity for a T.
+    fn parent_chunk_with_capacity<T>(&self) -> Option<&'p SingleArena> {
+        if self.exhausted.get() {
+            return None
+        }
+
+        let chunk = self.parent_chunk.filter(|chunk| fits(chunk, size_of::<T>(), align_of::<T>()));
+        if chunk.is_none() {
+            self.exhausted.set(true);
+        }
+        chunk
     }
 }
 
+impl Drop for ChildArena<'_> {
+    fn drop(&mut self) {
+        self.parent.children.set(self.parent.children.get() - 1);
+        let mut spare_chunks = self.parent.spare_chunks.borrow_mut();
+        for mut chunk in self.own.chunks.take().into_iter().chain(self.own.spare_chunks.get_mut().drain(..)) {
+            // chunks with leaked allocations are kept without being reset, as the memory may still be referenced
+            if chunk.allocations.get() == 0 {
+                chunk.reset();
+            }
+            // spare chunks are used from the end, so the parent's existing spare chunks are used first
+            spare_chunks.insert(0, chunk);
+        }
+    }
+}
+
+thread_local! {
+    static SCRATCH: RefCell<Arena> = const { RefCell::new(Arena::new()) };
+}
+
+/// Run `f` with the scratch arena of the current thread, so that an arena doesn't have to be passed through code.
+/// 
+/// Each thread has its own scratch arena. Allocations borrow the arena for the duration of `f`, so they can't escape it,
+/// and stay allocated until [`scratch_reset`] is called.
+/// 
+/// ```compile_fail
+/// use arena::ArenaAllocator;
+/// 
+/// let escaped = arena::scratch(|arena| arena.allocate(5));
+/// ```
+pub fn scratch<R>(f: impl FnOnce(&Arena) -> R) -> R {
+    SCRATCH.with(|arena| f(&arena.borrow()))
+}
+
+/// Free everything allocated in the scratch arena of the current thread, keeping its chunks to be reused.
+/// 
+/// # Panics
+/// If called from inside [`scratch`].
+pub fn scratch_reset() {
+    SCRATCH.with(|arena| arena.borrow_mut().reset())
+}
+
 impl ArenaAllocator<SingleArena> for Arena {
     fn new() -> Self {
-        Self { chunks: UnshrinkableLinkedList::new() }
+        Arena::new()
     }
 
     /// Allocate an object in an arena.
     /// 
     /// This may allocate on the heap if there is not enough capacity for the given object.
-    fn allocate<T>(&self, object: T) -> ArenaBox<T, SingleArena> {
+    fn allocate<T>(&self, object: T) -> ArenaBox<'_, T, SingleArena> {
+        self.notify_allocate::<T>();
         let allocation_size = size_of::<T>();
 
         if allocation_size == 0 {
+            // the box owns the zero sized value
+            std::mem::forget(object);
             return ArenaBox::new_zero_sized()
         }
 
-        let chunk_opt = self.chunks.last();
-        if let Some(chunk) = chunk_opt {
-            let remaining_capacity = chunk.remaining_capacity();
-            if allocation_size <= remaining_capacity {
-                return unsafe { chunk.allocate_unchecked(object) }
-            }
+        if let Some(chunk) = self.chunk_with_capacity(allocation_size, align_of::<T>()) {
+            return unsafe { chunk.allocate_unchecked(object) }
         }
 
         // create new chunk
-        unsafe {
-            self.new_chunk(size_of::<T>());
-            let chunk = self.chunks.last().unwrap();
-            return chunk.allocate_unchecked(object)
+        self.new_chunk(padded_size::<T>());
+        let chunk = self.chunks.last().unwrap();
+        unsafe { chunk.allocate_unchecked(object) }
+    }
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AllocateIn for Arena {
+    type Chunk = SingleArena;
+
+    /// Allocate an object in the arena, which always succeeds unless allocating a new chunk aborts the process.
+    fn allocate_in<T>(&self, object: T) -> Option<ArenaBox<'_, T, SingleArena>> {
+        Some(self.allocate(object))
+    }
+}
+
+impl AllocateIn for ChildArena<'_> {
+    type Chunk = SingleArena;
+
+    fn allocate_in<T>(&self, object: T) -> Option<ArenaBox<'_, T, SingleArena>> {
+        Some(self.allocate(object))
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        if let Some(pool) = &self.pool {
+            for chunk in self.chunks.take().into_iter().chain(self.spare_chunks.get_mut().drain(..)) {
+                pool.give(chunk);
+            }
         }
     }
 }
@@ -59,6 +651,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::unit_arg)]
     fn zero_sized_test() {
         let arena = Arena::new();
 
@@ -66,13 +659,299 @@ mod tests {
         assert_eq!(size_of::<()>(), 0);
 
         for _i in 0..1_000 {
-            let _ = arena.allocate(zst.clone());
+            let _ = arena.allocate(zst);
         }
 
         // no memory is actually allocated, therefore no chunks
         assert_eq!(arena.chunks.len(), 0);
     }
 
+    #[test]
+    fn first_fit_reuses_earlier_chunk() {
+        for first_fit in [false, true] {
+            let mut arena = Arena::new();
+            arena.chunk_size = 100;
+            arena.set_first_fit(first_fit);
+
+            let _first = arena.allocate([0u8; 60]);
+            // doesn't fit in the 40 bytes left in the first chunk
+            let _second = arena.allocate([0u8; 80]);
+            assert_eq!(arena.chunk_count(), 2);
+
+            // the last chunk has 20 bytes left, so by default a new chunk is created
+            let _third = arena.allocate([0u8; 30]);
+            assert_eq!(arena.chunk_count(), if first_fit { 2 } else { 3 });
+            let first_chunk_remaining = arena.chunks.iter().next().unwrap().remaining_capacity();
+            assert_eq!(first_chunk_remaining, if first_fit { 10 } else { 40 });
+        }
+    }
+
+    #[test]
+    fn drop_notifies_allocating_chunk() {
+        use std::rc::Rc;
+
+        // holds a counter, so that dropping it has a destructor to run
+        struct Counted(Rc<Cell<usize>>);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1)
+            }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+        let mut arena = Arena::new();
+        arena.chunk_size = 10 * size_of::<Counted>();
+
+        let mut allocations: Vec<_> = (0..15).map(|_| arena.allocate(Counted(Rc::clone(&drops)))).collect();
+        assert_eq!(arena.chunk_count(), 2);
+        let counts = |arena: &Arena| arena.chunks.iter().map(|chunk| chunk.allocations.get()).collect::<Vec<_>>();
+        assert_eq!(counts(&arena), [10, 5]);
+
+        // drop boxes from the first chunk only
+        allocations.drain(..4);
+        assert_eq!(drops.get(), 4);
+        assert_eq!(counts(&arena), [6, 5]);
+
+        drop(allocations);
+        assert_eq!(drops.get(), 15);
+        assert_eq!(counts(&arena), [0, 0]);
+    }
+
+    #[test]
+    fn allocation_hook() {
+        let sizes = Rc::new(RefCell::new(Vec::new()));
+        let mut arena = Arena::new();
+        arena.chunk_size = 16;
+        let recorded = Rc::clone(&sizes);
+        arena.on_allocate(move |size, align| recorded.borrow_mut().push((size, align)));
+
+        let _a = arena.allocate(1u8);
+        let _b = arena.allocate(2u64);
+        let _c = arena.allocate(());
+        // creates a new chunk
+        let _d = arena.try_allocate([0u32; 8]).unwrap();
+        let _e = arena.try_allocate(3u16).unwrap();
+        assert_eq!(*sizes.borrow(), [(1, 1), (8, 8), (0, 1), (32, 4), (2, 2)]);
+    }
+
+    #[test]
+    fn oversized_pool_chunk_isnt_used() {
+        let pool = ArenaPool::new(4);
+        {
+            let mut large = Arena::new_in(&pool);
+            large.chunk_size = 64 * 1024;
+            let _ = large.allocate(1u8);
+        }
+        assert_eq!(pool.len(), 1);
+
+        // the pooled chunk is larger than the maximum, so a new chunk is created instead of failing
+        let mut arena = Arena::new_in(&pool);
+        arena.set_max_total_bytes(16 * 1024);
+        assert_eq!(*arena.allocate(2u8), 2);
+        assert_eq!(arena.total_capacity(), CHUNK_SIZE);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.chunks_created(), 2);
+
+        // an allocation too large for the maximum fails, instead of aborting
+        assert!(arena.try_allocate([0u8; 16 * 1024]).is_err());
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut arena = Arena::new();
+        arena.chunk_size = 100;
+        let first = arena.allocate(1u64);
+
+        let snapshot = arena.snapshot();
+        let remaining_capacity = arena.chunks.last().unwrap().remaining_capacity();
+
+        for i in 0..30_u64 {
+            // leak the boxes, as they mustn't be dropped after restoring
+            std::mem::forget(arena.allocate(i));
+        }
+        assert_eq!(arena.chunk_count(), 3);
+
+        unsafe { arena.restore(snapshot) };
+        assert_eq!(arena.chunk_count(), 1);
+        assert_eq!(arena.chunks.last().unwrap().remaining_capacity(), remaining_capacity);
+        assert_eq!(arena.allocation_count(), 1);
+        assert_eq!(*first, 1);
+    }
+
+    #[test]
+    fn restore_keeps_spare_chunks() {
+        let arena = Arena::from_chunks([SingleArena::new(64).unwrap(), SingleArena::new(64).unwrap(), SingleArena::new(32).unwrap()]);
+        let snapshot = arena.snapshot();
+
+        for i in 0..20_u64 {
+            // leak the boxes, as they mustn't be dropped after restoring
+            std::mem::forget(arena.allocate(i));
+        }
+        assert_eq!(arena.chunk_count(), 3);
+
+        unsafe { arena.restore(snapshot) };
+        assert_eq!(arena.chunk_count(), 1);
+        assert_eq!(arena.total_capacity(), 160);
+
+        // the spare chunks are used again in their original order
+        let allocations: Vec<_> = (0..20_u64).map(|i| arena.allocate(i)).collect();
+        assert_eq!(arena.chunks.iter().map(|chunk| chunk.size()).collect::<Vec<_>>(), [64, 64, 32]);
+        assert_eq!(arena.total_capacity(), 160);
+        drop(allocations);
+    }
+
+    #[test]
+    #[should_panic(expected = "Arena restored while it has child arenas")]
+    fn restore_with_child_panics() {
+        let arena = Arena::new();
+        let _first = arena.allocate(1u64);
+        let snapshot = arena.snapshot();
+
+        let child = arena.child();
+        // the child's parent chunk would dangle if the chunk was removed
+        let _in_parent = child.allocate(2u64);
+        unsafe { arena.restore(snapshot) };
+    }
+
+    #[test]
+    fn restore_after_child_dropped() {
+        let arena = Arena::new();
+        let first = arena.allocate(1u64);
+        let snapshot = arena.snapshot();
+
+        {
+            let child = arena.child();
+            let _in_parent = child.allocate(2u64);
+        }
+        unsafe { arena.restore(snapshot) };
+        assert_eq!(*first, 1);
+        assert_eq!(arena.allocation_count(), 1);
+    }
+
+    #[test]
+    fn aligned_allocation_grows() {
+        #[repr(align(32))]
+        struct Aligned(u8);
+
+        // chunks are at least 16 byte aligned by the system allocator, so the chunk doesn't end on a 32 byte boundary
+        let arena = Arena::from_chunks([SingleArena::new(65).unwrap()]);
+        let _filler = arena.allocate([0u8; 33]);
+
+        let chunk = arena.chunks.last().unwrap();
+        // the size fits, but not with the padding
+        assert_eq!(chunk.remaining_capacity(), 32);
+        assert_ne!(chunk.get_free_pointer_mut().align_offset(32), 0);
+
+        let aligned = arena.allocate(Aligned(7));
+        assert_eq!(arena.chunk_count(), 2);
+        assert_eq!(&*aligned as *const Aligned as usize % 32, 0);
+        assert_eq!(aligned.0, 7);
+        assert!(arena.chunks.iter().all(|chunk| chunk.used_capacity() <= chunk.size()));
+    }
+
+    #[test]
+    fn max_total_bytes() {
+        let mut arena = Arena::new();
+        arena.set_max_total_bytes(16 * 1024);
+
+        let mut allocations = Vec::new();
+        for i in 0.. {
+            match arena.try_allocate([i as u8; 1000]) {
+                Ok(allocation) => allocations.push(allocation),
+                Err(object) => {
+                    assert_eq!(object[0], i as u8);
+                    break
+                }
+            }
+        }
+        assert_eq!(arena.total_capacity(), 16 * 1024);
+        assert_eq!(allocations.len(), 16);
+        assert!(arena.try_allocate([0u8; 1000]).is_err());
+
+        // smaller allocations still fit in the last chunk
+        let small = arena.try_allocate(1u8).ok().unwrap();
+        assert_eq!(*small, 1);
+        for (i, allocation) in allocations.iter().enumerate() {
+            assert!(allocation.iter().all(|&byte| byte == i as u8));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Arena would exceed its maximum total size of 100 bytes")]
+    fn allocate_past_max_total_bytes() {
+        let mut arena = Arena::new();
+        arena.set_max_total_bytes(100);
+        let _first = arena.allocate([0u8; 60]);
+        let _second = arena.allocate([0u8; 60]);
+    }
+
+    #[test]
+    fn child_shares_parent_chunk() {
+        let parent = Arena::new();
+        let _first = parent.allocate(1u64);
+        let used = parent.chunks.last().unwrap().used_capacity();
+
+        {
+            let child = parent.child();
+            let small = child.allocate(2u64);
+            // the small allocation is in the parent's chunk
+            assert_eq!(*small, 2);
+            assert_eq!(child.chunk_count(), 0);
+            assert_eq!(parent.chunks.last().unwrap().used_capacity(), used + 8);
+
+            let large = child.allocate([3u8; CHUNK_SIZE - 8]);
+            assert_eq!(large[0], 3);
+            assert_eq!(child.chunk_count(), 1);
+            // the child doesn't go back to the parent's chunk once it is full
+            let _after = child.allocate(4u8);
+            assert_eq!(parent.chunks.last().unwrap().used_capacity(), used + 8);
+        }
+
+        // the child's chunk is given to the parent
+        assert_eq!(parent.chunk_count(), 1);
+        assert_eq!(parent.total_capacity(), CHUNK_SIZE * 2);
+    }
+
+    #[test]
+    fn child_inherits_limit_and_hook() {
+        let mut parent = Arena::new();
+        parent.chunk_size = 64;
+        parent.set_max_total_bytes(128);
+        let recorded = Rc::new(RefCell::new(Vec::new()));
+        let hook_recorded = recorded.clone();
+        parent.on_allocate(move |size, align| hook_recorded.borrow_mut().push((size, align)));
+        let _first = parent.allocate(1u64);
+
+        {
+            let child = parent.child();
+            let _in_parent = child.allocate(2u32);
+            let _own = child.allocate([0u8; 64]);
+            // the child's chunk fills the parent's limit
+            assert!(child.try_allocate(3u8).is_err());
+        }
+
+        assert_eq!(parent.total_capacity(), 128);
+        assert_eq!(*recorded.borrow(), [(8, 8), (4, 4), (64, 1)]);
+    }
+
+    #[test]
+    fn allocate_in_generic() {
+        fn allocate_pair<A: AllocateIn>(arena: &A) -> (ArenaBox<'_, u32, A::Chunk>, ArenaBox<'_, String, A::Chunk>) {
+            (crate::allocate_in(arena, 1).unwrap(), crate::allocate_in(arena, String::from("two")).unwrap())
+        }
+
+        let chunk = SingleArena::new(100).unwrap();
+        let (number, string) = allocate_pair(&chunk);
+        assert_eq!((*number, string.as_str()), (1, "two"));
+        assert_eq!(chunk.allocations.get(), 2);
+
+        let arena = Arena::new();
+        let (number, string) = allocate_pair(&arena);
+        assert_eq!((*number, string.as_str()), (1, "two"));
+        assert_eq!(arena.allocation_count(), 2);
+    }
+
     #[test]
     fn allocate_three_chunks() {
         let integers_per_chunk = CHUNK_SIZE;
@@ -85,4 +964,201 @@ mod tests {
         assert_eq!(arena.chunks.len(), 3);
         assert!(arena.chunks.last().unwrap().remaining_capacity() < 8);
     }
+
+    #[cfg(feature = "debug_dump")]
+    #[test]
+    fn debug_dump_test() {
+        let arena = Arena::new();
+
+        let mut allocations = Vec::new();
+        for i in 0..(CHUNK_SIZE + 10) {
+            allocations.push(arena.allocate(i as u8));
+        }
+        // drop every other allocation
+        let allocations: Vec<_> = allocations.into_iter().step_by(2).collect();
+
+        let dump = arena.debug_dump();
+        assert_eq!(dump, vec![(0, CHUNK_SIZE, CHUNK_SIZE / 2), (1, 10, 5)]);
+        assert_eq!(dump.iter().map(|(_, _, count)| count).sum::<usize>(), allocations.len());
+    }
+
+    #[test]
+    fn from_chunks_fills_in_order() {
+        let first = SingleArena::new(1024).unwrap();
+        let second = SingleArena::new(1024).unwrap();
+        let (first_start, second_start) = (first.get_start_pointer_mut(), second.get_start_pointer_mut());
+
+        let arena = Arena::from_chunks([first, second]);
+        assert_eq!(arena.chunks.len(), 1);
+
+        for _i in 0..1024 {
+            let _ = arena.allocate(255u8);
+        }
+        assert_eq!(arena.chunks.len(), 1);
+        assert_eq!(arena.chunks.last().unwrap().get_start_pointer_mut(), first_start);
+
+        let _ = arena.allocate(255u8);
+        assert_eq!(arena.chunks.len(), 2);
+        assert_eq!(arena.chunks.last().unwrap().get_start_pointer_mut(), second_start);
+
+        // both chunks are full, so a new chunk is created
+        for _i in 0..1024 {
+            let _ = arena.allocate(255u8);
+        }
+        assert_eq!(arena.chunks.len(), 3);
+        assert_eq!(arena.chunks.last().unwrap().size(), CHUNK_SIZE);
+    }
+
+    #[test]
+    fn allocation_count_test() {
+        let arena = Arena::new();
+
+        let allocations: Vec<_> = (0..(CHUNK_SIZE + 100)).map(|i| arena.allocate(i as u8)).collect();
+        let _zst = arena.allocate(());
+        assert_eq!(arena.allocation_count(), CHUNK_SIZE + 100);
+
+        drop(allocations);
+        assert_eq!(arena.allocation_count(), 0);
+    }
+
+    #[test]
+    fn pool_bounds_heap_allocations() {
+        let pool = ArenaPool::new(4);
+
+        for _i in 0..100 {
+            let arena = Arena::new_in(&pool);
+            // fill two chunks
+            for _j in 0..(CHUNK_SIZE + 1) {
+                let _ = arena.allocate(255u8);
+            }
+        }
+
+        // only the first arena allocated its chunks on the heap
+        assert_eq!(pool.chunks_created(), 2);
+        assert_eq!(pool.len(), 2);
+
+        // a chunk larger than any in the pool is created
+        let arena = Arena::new_in(&pool);
+        let _ = arena.allocate([0u8; CHUNK_SIZE * 2]);
+        assert_eq!(pool.chunks_created(), 3);
+    }
+
+    #[test]
+    fn scratch_is_thread_local() {
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+        let threads: Vec<_> = (0..2_u64).map(|i| {
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                scratch(|arena| {
+                    let allocations: Vec<_> = (0..100).map(|_j| arena.allocate(i)).collect();
+                    // both threads have made their allocations
+                    barrier.wait();
+                    assert!(allocations.iter().all(|allocation| **allocation == i));
+                    assert_eq!(arena.allocation_count(), 100);
+                });
+
+                scratch_reset();
+                scratch(|arena| assert_eq!(arena.chunks.last().unwrap().remaining_capacity(), CHUNK_SIZE));
+            })
+        }).collect();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn grew_test() {
+        let arena = Arena::new();
+        assert_eq!(arena.chunk_count(), 0);
+        assert!(!arena.grew());
+
+        for _i in 0..(CHUNK_SIZE - 1) {
+            let _ = arena.allocate(255u8);
+        }
+        assert_eq!(arena.chunk_count(), 1);
+        assert!(!arena.grew());
+
+        let _ = arena.allocate(0u16);
+        assert_eq!(arena.chunk_count(), 2);
+        assert!(arena.grew());
+    }
+
+    #[test]
+    fn geometric_growth() {
+        let arena = Arena::with_growth_policy(GrowthPolicy { factor: 2.0, max_chunk_size: 1 << 20 });
+
+        // 4096 * (2^8 - 1) bytes fill 8 chunks, doubling up to 512 KiB
+        for _i in 0..(CHUNK_SIZE * 255) {
+            let _ = arena.allocate(255u8);
+        }
+        assert_eq!(arena.chunk_count(), 8);
+        assert_eq!(arena.chunks.last().unwrap().size(), CHUNK_SIZE << 7);
+
+        // growth stops at the max chunk size
+        for _i in 0..(3 << 20) {
+            let _ = arena.allocate(255u8);
+        }
+        assert_eq!(arena.chunk_count(), 11);
+        assert_eq!(arena.chunks.last().unwrap().size(), 1 << 20);
+    }
+
+    #[test]
+    fn trim_last_chunk_test() {
+        let mut arena = Arena::new();
+
+        let first: Vec<_> = (0..CHUNK_SIZE).map(|i| arena.allocate(i as u8)).collect();
+        let second = arena.allocate(0u8);
+        assert_eq!(arena.chunk_count(), 2);
+        drop(second);
+
+        let first_chunk = ArenaBox::arena(&first[0]).unwrap().get_start_pointer_mut();
+        drop(first);
+        assert!(arena.trim_last_chunk());
+        assert_eq!(arena.chunk_count(), 1);
+        assert_eq!(arena.chunks.last().unwrap().get_start_pointer_mut(), first_chunk);
+    }
+
+    #[test]
+    fn absorb_test() {
+        let mut first = Arena::new();
+        let second = Arena::new();
+        for i in 0..(CHUNK_SIZE + 1) {
+            std::mem::forget(first.allocate(i as u8));
+        }
+        let leaked: Vec<_> = (0..10_u64).map(|i| ArenaBox::into_raw(second.allocate(i)).0).collect();
+
+        // the allocations are leaked, so that the arena can be moved into `absorb`
+        assert!(first.absorb(second).is_ok());
+        assert_eq!(first.chunk_count(), 3);
+        assert_eq!(first.allocation_count(), CHUNK_SIZE + 11);
+        for (i, ptr) in leaked.iter().enumerate() {
+            assert_eq!(unsafe { *ptr.as_ptr() }, i as u64);
+        }
+
+        // the arena still allocates after absorbing
+        let allocation = first.allocate(10u64);
+        assert_eq!(*allocation, 10);
+        drop(allocation);
+
+        // free the leaked allocations
+        first.reset();
+        assert_eq!(first.allocation_count(), 0);
+    }
+
+    #[test]
+    fn absorb_over_maximum() {
+        let mut first = Arena::new();
+        first.set_max_total_bytes(CHUNK_SIZE * 2);
+        first.allocate(1u8);
+        let second = Arena::from_chunks([SingleArena::new(CHUNK_SIZE + 1).unwrap()]);
+
+        let second = first.absorb(second).err().unwrap();
+        assert_eq!(first.total_capacity(), CHUNK_SIZE);
+        assert_eq!(second.total_capacity(), CHUNK_SIZE + 1);
+
+        let third = Arena::from_chunks([SingleArena::new(CHUNK_SIZE).unwrap()]);
+        assert!(first.absorb(third).is_ok());
+        assert_eq!(first.total_capacity(), CHUNK_SIZE * 2);
+    }
 }
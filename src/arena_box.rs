@@ -1,10 +1,60 @@
 use std::{
-    ops::{Deref, DerefMut}, ptr::NonNull, marker::PhantomData
+    ops::{Deref, DerefMut}, ptr::NonNull, marker::PhantomData, mem::{size_of, align_of, MaybeUninit}, any::Any
 };
 use super::ArenaChunk;
 
 /// A wrapper around a pointer that owns memory allocated in an arena.
-pub struct ArenaBox<'a, T, A: ArenaChunk> {
+/// 
+/// The box holds a raw pointer to the object, and a reference to the chunk which it notifies when dropped,
+/// so it is neither `Send` nor `Sync`.
+/// 
+/// # Lifetime
+/// The box borrows its arena, so the arena can't be dropped or moved while the box exists.
+/// 
+/// ```compile_fail
+/// use arena::{ArenaChunk, single_chunk::SingleArena};
+/// 
+/// let chunk = SingleArena::new(64).unwrap();
+/// let allocation = chunk.allocate(5).unwrap();
+/// drop(chunk);
+/// assert_eq!(*allocation, 5);
+/// ```
+/// 
+/// ```compile_fail
+/// use arena::{Arena, ArenaAllocator};
+/// 
+/// let arena = Arena::new();
+/// let allocation = arena.allocate(5);
+/// drop(arena);
+/// assert_eq!(*allocation, 5);
+/// ```
+/// 
+/// ```compile_fail
+/// use arena::{Arena, ArenaAllocator};
+/// 
+/// let arena = Arena::new();
+/// let allocation = arena.allocate(5);
+/// let moved = arena;
+/// assert_eq!(*allocation, 5);
+/// ```
+/// 
+/// Dropping the box before the arena is allowed.
+/// 
+/// ```
+/// use arena::{Arena, ArenaAllocator, ArenaChunk, single_chunk::SingleArena};
+/// 
+/// let chunk = SingleArena::new(64).unwrap();
+/// let allocation = chunk.allocate(5).unwrap();
+/// drop(allocation);
+/// drop(chunk);
+/// 
+/// let arena = Arena::new();
+/// let allocation = arena.allocate(5);
+/// drop(allocation);
+/// let moved = arena;
+/// drop(moved);
+/// ```
+pub struct ArenaBox<'a, T: ?Sized, A: ArenaChunk> {
     inner: NonNull<T>,
     // Zero Sized Types don't belong to an arena chunk
     arena: Option<&'a A>,
@@ -12,13 +62,77 @@ pub struct ArenaBox<'a, T, A: ArenaChunk> {
     phantom: PhantomData<T>
 }
 
-impl<'a, T, A: ArenaChunk> ArenaBox<'a, T, A> {
+impl<'a, T: ?Sized, A: ArenaChunk> ArenaBox<'a, T, A> {
     /// # Safety
     /// Non-null pointer must be aligned, and point to a valid T.
     pub unsafe fn new(arena: &'a A, object: NonNull<T>) -> Self {
         Self { inner: object, arena: Some(arena), phantom: PhantomData }
     }
 
+    /// Returns the arena chunk that the T was allocated in, or None for zero sized types, which don't belong to a chunk.
+    pub fn arena(arena_box: &ArenaBox<'a, T, A>) -> Option<&'a A> {
+        arena_box.arena
+    }
+
+    /// Returns a mut pointer to the T allocated in the arena.
+    /// 
+    /// # Safety
+    /// Pointer must not be used after the arena box is dropped.
+    pub unsafe fn mut_ptr(arena_box: &mut ArenaBox<'_, T, A>) -> *mut T {
+        arena_box.inner.as_mut()
+    }
+
+    /// Returns a const pointer to the T allocated in the arena.
+    /// 
+    /// # Safety
+    /// Pointer must not be used after the arena box is dropped.
+    pub unsafe fn const_ptr(arena_box: &ArenaBox<'_, T, A>) -> *const T {
+        arena_box.inner.as_ptr()
+    }
+
+    /// Consumes the box, and returns the pointer to the T and the chunk it was allocated in, like `Box::into_raw`.
+    /// 
+    /// The T isn't dropped, and the allocation is still counted by the chunk until the box is rebuilt with [`from_raw`](ArenaBox::from_raw) and dropped.
+    pub fn into_raw(arena_box: ArenaBox<'a, T, A>) -> (NonNull<T>, Option<&'a A>) {
+        let raw = (arena_box.inner, arena_box.arena);
+        std::mem::forget(arena_box);
+        raw
+    }
+
+    /// Rebuilds a box from the pointer and chunk returned by [`into_raw`](ArenaBox::into_raw).
+    /// 
+    /// # Safety
+    /// The pointer and chunk must have been returned by `into_raw`, and the box must only be rebuilt once.
+    pub unsafe fn from_raw(ptr: NonNull<T>, arena: Option<&'a A>) -> Self {
+        Self { inner: ptr, arena, phantom: PhantomData }
+    }
+
+    /// Notify the chunk that the allocation is unused. The T must have been dropped or moved out.
+    unsafe fn drop_notify_arena(&self) {
+        // only notify the chunk if T isn't a ZST
+        if let Some(arena_ref) = self.arena { 
+            arena_ref.free_allocation(self.inner.as_ptr().cast::<u8>());
+        }
+    }
+}
+
+/// Notifies a chunk that an allocation is unused when dropped, so that it is notified after a destructor runs, even if it panics.
+struct FreeGuard<'a, A: ArenaChunk> {
+    // None for zero sized types, which don't belong to a chunk
+    arena: Option<&'a A>,
+    ptr: *mut u8
+}
+
+impl<'a, A: ArenaChunk> Drop for FreeGuard<'a, A> {
+    fn drop(&mut self) {
+        if let Some(arena) = self.arena {
+            // safety: the guard is only made for an allocation in the chunk, which isn't used afterwards
+            unsafe { arena.free_allocation(self.ptr) }
+        }
+    }
+}
+
+impl<'a, T, A: ArenaChunk> ArenaBox<'a, T, A> {
     pub fn new_zero_sized() -> Self {
         Self { inner: NonNull::dangling(), arena: None, phantom: PhantomData }
     }
@@ -27,40 +141,257 @@ impl<'a, T, A: ArenaChunk> ArenaBox<'a, T, A> {
     pub fn into_inner(arena_box: ArenaBox<'a, T, A>) -> T {
         let ptr = arena_box.inner.as_ptr();
 
-        // self isn't going to be dropped, so notify the arena that the allocation will be unused
-        unsafe { arena_box.drop_notify_arena() };
+        unsafe {
+            let object = std::ptr::read(ptr);
+
+            // self isn't going to be dropped, so notify the arena that the allocation is unused, once T has been moved out
+            arena_box.drop_notify_arena();
+
+            // don't run drop on self as it will call drop on T
+            std::mem::forget(arena_box);
+            object
+        }
+    }
+
+    /// Converts the box into a box of an unsized type, such as a trait object, like the unsizing coercion of `Box`.
+    /// 
+    /// `coerce` performs the coercion on a reference, e.g. `|value| value as &mut dyn Display`.
+    /// The box is dropped through the new pointer, so a trait object's destructor runs through its vtable.
+    /// 
+    /// # Panics
+    /// If the returned reference doesn't point to the whole T.
+    pub fn unsize<U: ?Sized>(mut arena_box: ArenaBox<'a, T, A>, coerce: impl for<'r> FnOnce(&'r mut T) -> &'r mut U) -> ArenaBox<'a, U, A> {
+        let inner = NonNull::from(coerce(&mut *arena_box));
+        // safety: the reference was just created from a valid U
+        let unsized_size = std::mem::size_of_val(unsafe { inner.as_ref() });
+        assert!(
+            std::ptr::addr_eq(inner.as_ptr(), arena_box.inner.as_ptr()) && unsized_size == size_of::<T>(),
+            "Coerced reference must point to the whole object"
+        );
 
-        // don't run drop on self as it will call drop on T
+        let arena = arena_box.arena;
+        // the new box owns T, and keeps the allocation count
         std::mem::forget(arena_box);
+        ArenaBox { inner, arena, phantom: PhantomData }
+    }
 
-        unsafe { std::ptr::read(ptr) }
+    /// Moves the T out from the arena if `pred` returns true for it, like `into_inner`, otherwise returns the box unchanged.
+    pub fn take_if<F: FnOnce(&T) -> bool>(arena_box: ArenaBox<'a, T, A>, pred: F) -> Result<T, ArenaBox<'a, T, A>> {
+        if pred(&arena_box) {
+            Ok(Self::into_inner(arena_box))
+        } else {
+            Err(arena_box)
+        }
     }
 
-    /// Returns a mut pointer to the T allocated in the arena.
+    /// Clones the T onto the heap, so it can outlive the arena.
+    pub fn to_box(arena_box: &ArenaBox<'a, T, A>) -> Box<T> where T: Clone {
+        Box::new((**arena_box).clone())
+    }
+
+    /// Moves a new value into the allocation, and returns the old value, like `std::mem::replace`.
+    /// 
+    /// The old value isn't dropped, and the allocation count and free pointer of the arena are unchanged.
+    pub fn replace(arena_box: &mut ArenaBox<'a, T, A>, value: T) -> T {
+        std::mem::replace(&mut **arena_box, value)
+    }
+
+    /// Moves the T out from the arena, applies `f` to it, and allocates the result in the same arena.
+    ///
+    /// If U fits in the slot used by T, the slot is reused. Otherwise a fresh allocation is made,
+    /// which returns None if the arena doesn't have the capacity for U.
+    ///
+    /// A zero sized T doesn't belong to an arena, so this returns None unless U is also zero sized.
+    pub fn map<U, F: FnOnce(T) -> U>(arena_box: ArenaBox<'a, T, A>, f: F) -> Option<ArenaBox<'a, U, A>> {
+        let (ptr, arena) = Self::into_raw(arena_box);
+
+        // the slot is freed if f panics, or the slot isn't reused.
+        // safety: the box has been consumed, so T can be moved out
+        let guard = FreeGuard { arena, ptr: ptr.as_ptr().cast::<u8>() };
+        let mapped = f(unsafe { std::ptr::read(ptr.as_ptr()) });
+
+        if size_of::<U>() == 0 {
+            // the box now owns the zero sized value
+            std::mem::forget(mapped);
+            return Some(ArenaBox::new_zero_sized())
+        }
+
+        let arena = arena?;
+        if size_of::<U>() <= size_of::<T>() && align_of::<U>() <= align_of::<T>() {
+            // the allocation is still counted, and is kept for U
+            std::mem::forget(guard);
+
+            // safety: the slot is aligned for T, and alignments are powers of two, so it is
+            // aligned for U. the slot is large enough for U and T has been moved out of it.
+            unsafe {
+                let ptr = ptr.cast::<U>();
+                std::ptr::write(ptr.as_ptr(), mapped);
+                Some(ArenaBox::new(arena, ptr))
+            }
+        } else {
+            drop(guard);
+            arena.allocate(mapped)
+        }
+    }
+}
+
+impl<'a, T, A: ArenaChunk> ArenaBox<'a, [T], A> {
+    /// Create a box of an empty slice, which doesn't belong to an arena chunk.
+    pub fn new_empty_slice() -> Self {
+        Self { inner: NonNull::slice_from_raw_parts(NonNull::dangling(), 0), arena: None, phantom: PhantomData }
+    }
+
+    /// Create a box of a slice of `len` zero sized Ts, which doesn't belong to an arena chunk.
     /// 
     /// # Safety
-    /// Pointer must not be used after the arena box is dropped.
-    pub unsafe fn mut_ptr(arena_box: &mut ArenaBox<'_, T, A>) -> *mut T {
-        arena_box.inner.as_mut()
+    /// T must be zero sized, and the box takes ownership of `len` Ts which must have been forgotten.
+    pub unsafe fn new_zero_sized_slice(len: usize) -> Self {
+        Self { inner: NonNull::slice_from_raw_parts(NonNull::dangling(), len), arena: None, phantom: PhantomData }
     }
 
-    /// Returns a const pointer to the T allocated in the arena.
+    /// Clone the elements into a `Vec`, which doesn't borrow the arena, and free the allocation.
+    /// 
+    /// Elements that are `Copy` are copied in one go, as `<[T]>::to_vec` does.
+    pub fn into_vec(arena_box: ArenaBox<'a, [T], A>) -> Vec<T> where T: Clone {
+        arena_box.to_vec()
+    }
+}
+
+impl<'a, T, A: ArenaChunk, const N: usize> ArenaBox<'a, [T; N], A> {
+    /// Convert a box of an array to a box of a slice of its elements, without moving them.
+    pub fn as_slice_box(arena_box: ArenaBox<'a, [T; N], A>) -> ArenaBox<'a, [T], A> {
+        let (ptr, arena) = ArenaBox::into_raw(arena_box);
+        // safety: the slice has the same address and elements as the array, so the chunk is notified of the same allocation
+        unsafe { ArenaBox::from_raw(NonNull::slice_from_raw_parts(ptr.cast::<T>(), N), arena) }
+    }
+}
+
+impl<'b, 'a, T, A: ArenaChunk> IntoIterator for &'b ArenaBox<'a, [T], A> {
+    type Item = &'b T;
+    type IntoIter = std::slice::Iter<'b, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'b, 'a, T, A: ArenaChunk> IntoIterator for &'b mut ArenaBox<'a, [T], A> {
+    type Item = &'b mut T;
+    type IntoIter = std::slice::IterMut<'b, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Moves the elements out of a boxed slice, like `vec::IntoIter`.
+impl<'a, T, A: ArenaChunk> IntoIterator for ArenaBox<'a, [T], A> {
+    type Item = T;
+    type IntoIter = SliceIntoIter<'a, T, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let (ptr, arena) = ArenaBox::into_raw(self);
+        SliceIntoIter { start: ptr.cast::<T>(), front: 0, back: ptr.len(), arena, phantom: PhantomData }
+    }
+}
+
+/// An iterator which moves the elements out of an `ArenaBox<[T]>`.
+/// 
+/// The elements that haven't been yielded are dropped with the iterator, which then frees the allocation like the box would.
+pub struct SliceIntoIter<'a, T, A: ArenaChunk> {
+    start: NonNull<T>,
+    // the elements in front..back haven't been yielded
+    front: usize,
+    back: usize,
+    arena: Option<&'a A>,
+    // the iterator owns the remaining Ts
+    phantom: PhantomData<T>
+}
+
+impl<'a, T, A: ArenaChunk> SliceIntoIter<'a, T, A> {
+    /// The elements that haven't been yielded.
+    pub fn as_slice(&self) -> &[T] {
+        // safety: the elements in front..back are initialised, and owned by the iterator
+        unsafe { std::slice::from_raw_parts(self.start.as_ptr().add(self.front), self.back - self.front) }
+    }
+}
+
+impl<'a, T, A: ArenaChunk> Iterator for SliceIntoIter<'a, T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None
+        }
+
+        // safety: the element hasn't been yielded, and won't be read again
+        let item = unsafe { std::ptr::read(self.start.as_ptr().add(self.front)) };
+        self.front += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T, A: ArenaChunk> DoubleEndedIterator for SliceIntoIter<'a, T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None
+        }
+
+        self.back -= 1;
+        // safety: the element hasn't been yielded, and won't be read again
+        Some(unsafe { std::ptr::read(self.start.as_ptr().add(self.back)) })
+    }
+}
+
+impl<'a, T, A: ArenaChunk> ExactSizeIterator for SliceIntoIter<'a, T, A> {}
+
+impl<'a, T, A: ArenaChunk> Drop for SliceIntoIter<'a, T, A> {
+    fn drop(&mut self) {
+        // zero sized slices don't belong to a chunk.
+        // the chunk is notified after the remaining elements are dropped
+        let _guard = FreeGuard { arena: self.arena, ptr: self.start.as_ptr().cast::<u8>() };
+
+        // drop the remaining elements without deallocating the memory
+        // safety: the elements in front..back are initialised, and owned by the iterator
+        unsafe {
+            let remaining = std::ptr::slice_from_raw_parts_mut(self.start.as_ptr().add(self.front), self.back - self.front);
+            std::ptr::drop_in_place(remaining)
+        }
+    }
+}
+
+impl<'a, T, A: ArenaChunk> ArenaBox<'a, [MaybeUninit<T>], A> {
+    /// Converts a box of a slice of `MaybeUninit<T>`s, such as from [`allocate_uninit_slice`](ArenaChunk::allocate_uninit_slice), to a box of a slice of Ts.
     /// 
     /// # Safety
-    /// Pointer must not be used after the arena box is dropped.
-    pub unsafe fn const_ptr(arena_box: &ArenaBox<'_, T, A>) -> *const T {
-        arena_box.inner.as_ptr()
+    /// Every element of the slice must be initialised.
+    pub unsafe fn assume_init(arena_box: ArenaBox<'a, [MaybeUninit<T>], A>) -> ArenaBox<'a, [T], A> {
+        let (ptr, arena) = Self::into_raw(arena_box);
+        ArenaBox::from_raw(NonNull::slice_from_raw_parts(ptr.cast::<T>(), ptr.len()), arena)
     }
+}
 
-    unsafe fn drop_notify_arena(&self) {
-        // only adjust allocation count and drop T if T isn't a ZST
-        if let Some(arena_ref) = self.arena { 
-            arena_ref.adjust_allocation_count(-1);
+impl<'a, A: ArenaChunk> ArenaBox<'a, dyn Any, A> {
+    /// Attempts to downcast the box to a concrete type, like `Box::downcast`.
+    /// 
+    /// The pointer is reused, so nothing is reallocated. The original box is returned if the object isn't a T.
+    pub fn downcast<T: Any>(arena_box: ArenaBox<'a, dyn Any, A>) -> Result<ArenaBox<'a, T, A>, ArenaBox<'a, dyn Any, A>> {
+        if !(*arena_box).is::<T>() {
+            return Err(arena_box)
         }
+
+        let (ptr, arena) = Self::into_raw(arena_box);
+        // safety: the object is a T, so the thin pointer points to a valid T, and the allocation is still counted
+        Ok(unsafe { ArenaBox::from_raw(ptr.cast::<T>(), arena) })
     }
 }
 
-impl<'a, T, A: ArenaChunk> Deref for ArenaBox<'a, T, A> {
+impl<'a, T: ?Sized, A: ArenaChunk> Deref for ArenaBox<'a, T, A> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
         // safety: shared reference to self allows a shared reference to the inner T
@@ -68,23 +399,60 @@ impl<'a, T, A: ArenaChunk> Deref for ArenaBox<'a, T, A> {
     }
 }
 
-impl<'a, T, A: ArenaChunk> DerefMut for ArenaBox<'a, T, A> {
+impl<'a, T: ?Sized, A: ArenaChunk> DerefMut for ArenaBox<'a, T, A> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         // safety: unique reference to self allows a unique reference to the inner T
         unsafe { self.inner.as_mut() }
     }
 }
 
-impl<'a, T, A: ArenaChunk> Drop for ArenaBox<'a, T, A> {
+impl<'a, T: ?Sized, A: ArenaChunk> AsRef<T> for ArenaBox<'a, T, A> {
+    fn as_ref(&self) -> &T {
+        // a dangling pointer for a zero sized type is still valid to reference
+        self
+    }
+}
+
+impl<'a, T: ?Sized, A: ArenaChunk> AsMut<T> for ArenaBox<'a, T, A> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<'a, T: ?Sized + PartialEq, A: ArenaChunk> PartialEq for ArenaBox<'a, T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<'a, T: ?Sized + Eq, A: ArenaChunk> Eq for ArenaBox<'a, T, A> {}
+
+impl<'a, T: ?Sized + PartialOrd, A: ArenaChunk> PartialOrd for ArenaBox<'a, T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<'a, T: ?Sized + Ord, A: ArenaChunk> Ord for ArenaBox<'a, T, A> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+/// Allows coercing a box to a box of an unsized type, e.g. `let display: ArenaBox<dyn Display, _> = arena_box;`.
+#[cfg(feature = "nightly")]
+impl<'a, T: ?Sized + std::marker::Unsize<U>, U: ?Sized, A: ArenaChunk> std::ops::CoerceUnsized<ArenaBox<'a, U, A>> for ArenaBox<'a, T, A> {}
+
+impl<'a, T: ?Sized, A: ArenaChunk> Drop for ArenaBox<'a, T, A> {
     fn drop(&mut self) {
-        unsafe {
-            // safe to do when dropping self
-            self.drop_notify_arena();
+        // the chunk is notified once T has been dropped, as it may reuse the memory
+        let _guard = FreeGuard { arena: self.arena, ptr: self.inner.as_ptr().cast::<u8>() };
 
+        unsafe {
             // call T's destructor without deallocating the memory
             // this has the only pointer to T, and since this struct is being dropped, T can be dropped
             // safety: NonNull<T> is valid and properly aligned
-            drop(std::ptr::read(self.inner.as_ptr()))
+            std::ptr::drop_in_place(self.inner.as_ptr())
         }
     }
 }
@@ -93,6 +461,7 @@ impl<'a, T, A: ArenaChunk> Drop for ArenaBox<'a, T, A> {
 mod tests {
     use super::*;
     use crate::{Arena, ArenaAllocator};
+    use crate::single_chunk::SingleArena;
 
     #[test]
     fn into_inner_test() {
@@ -130,4 +499,276 @@ mod tests {
         drop(second_allocation);
         assert_eq!(arena.chunks.last().unwrap().allocations.get(), 0);
     }
+
+    #[test]
+    fn map_test() {
+        let arena = SingleArena::new(100).unwrap();
+
+        let allocation = arena.allocate(5i32).unwrap();
+        let mapped = ArenaBox::map(allocation, |i| i.to_string().repeat(3)).unwrap();
+        assert_eq!(*mapped, "555");
+        assert_eq!(arena.allocations.get(), 1);
+
+        // u16 fits in the slot of the string, so it is reused
+        let start = arena.get_free_pointer_mut();
+        let mapped = ArenaBox::map(mapped, |s| s.len() as u16).unwrap();
+        assert_eq!(*mapped, 3);
+        assert_eq!(start, arena.get_free_pointer_mut());
+        assert_eq!(arena.allocations.get(), 1);
+    }
+
+    #[test]
+    fn as_ref_test() {
+        fn string_length(string: impl AsRef<String>) -> usize {
+            string.as_ref().len()
+        }
+        fn str_length(string: impl AsRef<str>) -> usize {
+            string.as_ref().len()
+        }
+
+        let arena = SingleArena::new(100).unwrap();
+        let mut allocation = arena.allocate(String::from("arena")).unwrap();
+        allocation.as_mut().push_str(" box");
+
+        assert_eq!(str_length(allocation.as_ref()), 9);
+        assert_eq!(string_length(allocation), 9);
+
+        let zst = arena.allocate(()).unwrap();
+        assert_eq!(zst.as_ref(), &());
+    }
+
+    #[test]
+    fn replace_test() {
+        let arena = SingleArena::new(100).unwrap();
+        let mut allocation = arena.allocate(String::from("old")).unwrap();
+        let free_pointer = arena.get_free_pointer_mut();
+
+        let old = ArenaBox::replace(&mut allocation, String::from("new"));
+        assert_eq!(old, "old");
+        assert_eq!(*allocation, "new");
+        assert_eq!(arena.get_free_pointer_mut(), free_pointer);
+        assert_eq!(arena.allocations.get(), 1);
+    }
+
+    #[test]
+    fn arena_test() {
+        let arena = SingleArena::new(100).unwrap();
+        let allocation = arena.allocate(1u32).unwrap();
+
+        let sibling = ArenaBox::arena(&allocation).unwrap().allocate(2u32).unwrap();
+        assert_eq!((*allocation, *sibling), (1, 2));
+        assert_eq!(arena.allocations.get(), 2);
+
+        let zst = arena.allocate(()).unwrap();
+        assert!(ArenaBox::arena(&zst).is_none());
+    }
+
+    #[test]
+    fn to_box_test() {
+        let arena = SingleArena::new(100).unwrap();
+        let allocation = arena.allocate(String::from("heap")).unwrap();
+        let zst = arena.allocate(()).unwrap();
+
+        let boxed = ArenaBox::to_box(&allocation);
+        let boxed_zst = ArenaBox::to_box(&zst);
+        drop((allocation, zst));
+        drop(arena);
+
+        assert_eq!(*boxed, "heap");
+        assert_eq!(*boxed_zst, ());
+    }
+
+    #[test]
+    fn unsize_to_trait_object() {
+        use std::fmt::Display;
+        use std::cell::Cell;
+
+        struct Loud<'a>(&'a str, &'a Cell<usize>);
+        impl Display for Loud<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}!", self.0.to_uppercase())
+            }
+        }
+        impl Drop for Loud<'_> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let arena = SingleArena::new(200).unwrap();
+        let objects: Vec<ArenaBox<'_, dyn Display, _>> = vec![
+            arena.allocate_dyn(5u8, |value| value as &mut dyn Display).unwrap(),
+            arena.allocate_dyn(String::from("arena"), |value| value as &mut dyn Display).unwrap(),
+            arena.allocate_dyn(Loud("box", &drops), |value| value as &mut dyn Display).unwrap()
+        ];
+
+        let formatted: Vec<_> = objects.iter().map(|object| object.to_string()).collect();
+        assert_eq!(formatted, ["5", "arena", "BOX!"]);
+        assert_eq!(arena.allocations.get(), 3);
+
+        drop(objects);
+        assert_eq!(drops.get(), 1);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn coerce_to_trait_object() {
+        use std::fmt::Debug;
+
+        let arena = SingleArena::new(100).unwrap();
+        let values: [ArenaBox<'_, dyn Debug, _>; 2] = [arena.allocate(5u64).unwrap(), arena.allocate("coerced").unwrap()];
+
+        let formatted: Vec<_> = values.iter().map(|value| format!("{:?}", &**value)).collect();
+        assert_eq!(formatted, ["5", "\"coerced\""]);
+        drop(values);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        let arena = SingleArena::new(100).unwrap();
+        let allocation = arena.allocate(String::from("raw")).unwrap();
+        let slice = arena.allocate_iter_exact([1u8, 2, 3]).unwrap();
+
+        let (ptr, chunk) = ArenaBox::into_raw(allocation);
+        let (slice_ptr, slice_chunk) = ArenaBox::into_raw(slice);
+        assert!(std::ptr::eq(chunk.unwrap(), &arena));
+        assert_eq!(arena.allocations.get(), 2);
+
+        let allocation = unsafe { ArenaBox::from_raw(ptr, chunk) };
+        let slice = unsafe { ArenaBox::from_raw(slice_ptr, slice_chunk) };
+        assert_eq!(*allocation, "raw");
+        assert_eq!(*slice, [1, 2, 3]);
+        assert_eq!(arena.allocations.get(), 2);
+
+        drop((allocation, slice));
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn downcast_test() {
+        let arena = SingleArena::new(100).unwrap();
+        let any = arena.allocate_dyn(5u32, |value| value as &mut dyn Any).unwrap();
+
+        let any = ArenaBox::downcast::<String>(any).err().unwrap();
+        assert_eq!(arena.allocations.get(), 1);
+
+        let free_pointer = arena.get_free_pointer_mut();
+        let integer = ArenaBox::downcast::<u32>(any).ok().unwrap();
+        assert_eq!(*integer, 5);
+        assert_eq!(arena.get_free_pointer_mut(), free_pointer);
+        assert_eq!(arena.allocations.get(), 1);
+
+        drop(integer);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    // the ordering only depends on the T, not the chunk's cells
+    #[allow(clippy::mutable_key_type)]
+    fn ordered_set() {
+        use std::collections::BTreeSet;
+
+        let arena = SingleArena::new(100).unwrap();
+        let set: BTreeSet<_> = [5, -3, 12, 0, 5].into_iter().map(|i: i32| arena.allocate(i).unwrap()).collect();
+
+        // the duplicate 5 is equal, so isn't inserted
+        assert_eq!(set.iter().map(|i| **i).collect::<Vec<_>>(), [-3, 0, 5, 12]);
+        assert_eq!(arena.allocations.get(), 4);
+        assert!(arena.allocate(1).unwrap() < arena.allocate(2).unwrap());
+    }
+
+    #[test]
+    fn take_if_test() {
+        use std::cell::Cell;
+
+        struct DropCounter<'a>(&'a Cell<usize>, u32);
+        impl Drop for DropCounter<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let arena = SingleArena::new(100).unwrap();
+        let allocations: Vec<_> = (0..4).map(|i| arena.allocate(DropCounter(&drops, i)).unwrap()).collect();
+
+        let (taken, kept): (Vec<_>, Vec<_>) = allocations.into_iter()
+            .map(|allocation| ArenaBox::take_if(allocation, |counter| counter.1 % 2 == 0))
+            .partition(Result::is_ok);
+        assert_eq!(drops.get(), 0);
+        assert_eq!(arena.allocations.get(), 2);
+
+        let taken: Vec<_> = taken.into_iter().map(|value| value.ok().unwrap()).collect();
+        assert_eq!(taken.iter().map(|counter| counter.1).collect::<Vec<_>>(), [0, 2]);
+        drop(taken);
+        assert_eq!(drops.get(), 2);
+
+        drop(kept);
+        assert_eq!(drops.get(), 4);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn iterate_slice_by_reference() {
+        let arena = SingleArena::new(1024).unwrap();
+        let mut slice = arena.allocate_iter_exact(["a", "b", "c"].map(String::from)).unwrap();
+
+        for string in &mut slice {
+            string.push('!');
+        }
+        let mut joined = String::new();
+        for string in &slice {
+            joined.push_str(string);
+        }
+        assert_eq!(joined, "a!b!c!");
+    }
+
+    #[test]
+    fn iterate_slice_by_value() {
+        let arena = SingleArena::new(1024).unwrap();
+        let slice = arena.allocate_iter_exact(["a", "b", "c", "d"].map(String::from)).unwrap();
+
+        let mut iter = slice.into_iter();
+        assert_eq!(iter.next().unwrap(), "a");
+        assert_eq!(iter.next_back().unwrap(), "d");
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.as_slice(), ["b", "c"]);
+        // the remaining strings are dropped, and the allocation is freed
+        drop(iter);
+        assert_eq!(arena.allocations.get(), 0);
+
+        let slice = arena.allocate_iter_exact(["e", "f"].map(String::from)).unwrap();
+        let strings: Vec<String> = slice.into_iter().collect();
+        assert_eq!(strings, ["e", "f"]);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn array_as_slice_box() {
+        let arena = SingleArena::new(100).unwrap();
+        let array = arena.allocate([1u32, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+
+        let slice: ArenaBox<[u32], _> = ArenaBox::as_slice_box(array);
+        assert_eq!(slice.len(), 8);
+        assert_eq!(slice.iter().sum::<u32>(), 36);
+        assert_eq!(arena.allocations.get(), 1);
+        drop(slice);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn slice_into_vec() {
+        let vec = {
+            let arena = SingleArena::new(100).unwrap();
+            let slice = arena.allocate_slice(&[1u32, 2, 3, 4]).unwrap().into_box();
+            let vec = ArenaBox::into_vec(slice);
+            assert_eq!(arena.allocations.get(), 0);
+            vec
+        };
+        // the arena has been dropped
+        assert_eq!(vec, [1, 2, 3, 4]);
+    }
 }
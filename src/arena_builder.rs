@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::single_chunk::SingleArena;
+use super::arena_allocator::CHUNK_SIZE;
+use super::{Arena, ArenaChunk, GrowthPolicy};
+
+/// Configures and creates an [`Arena`].
+/// 
+/// ```
+/// use arena::ArenaBuilder;
+/// 
+/// let arena = ArenaBuilder::new()
+///     .chunk_size(16384)
+///     .initial_capacity(65536)
+///     .growth_factor(2.0)
+///     .zeroize(true)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ArenaBuilder {
+    chunk_size: usize,
+    initial_capacity: usize,
+    growth_factor: f64,
+    zeroize: bool,
+    poison: bool,
+    first_fit: bool,
+    max_total_bytes: usize
+}
+
+/// The reason that [`ArenaBuilder::build`] rejected its settings.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArenaBuilderError {
+    /// The chunk size is 0 or greater than isize::MAX.
+    InvalidChunkSize,
+    /// The initial capacity is greater than isize::MAX.
+    InvalidInitialCapacity,
+    /// The growth factor is less than 1, or isn't finite.
+    InvalidGrowthFactor,
+    /// The initial capacity is greater than the maximum total size.
+    InitialCapacityExceedsMaximum
+}
+
+impl fmt::Display for ArenaBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidChunkSize => write!(f, "chunk size must be between 1 and isize::MAX"),
+            Self::InvalidInitialCapacity => write!(f, "initial capacity must not be greater than isize::MAX"),
+            Self::InvalidGrowthFactor => write!(f, "growth factor must be finite and at least 1"),
+            Self::InitialCapacityExceedsMaximum => write!(f, "initial capacity must not be greater than the maximum total size")
+        }
+    }
+}
+
+impl std::error::Error for ArenaBuilderError {}
+
+impl ArenaBuilder {
+    /// Create a builder with the settings of [`Arena::new`].
+    pub fn new() -> Self {
+        Self { chunk_size: CHUNK_SIZE, initial_capacity: 0, growth_factor: 1.0, zeroize: false, poison: cfg!(feature = "poison"), first_fit: false, max_total_bytes: usize::MAX }
+    }
+
+    /// The minimum size of each new chunk, 4096 bytes by default.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// The size of a first chunk, which is created by `build`. By default it is 0, so no chunk is created until the first allocation.
+    pub fn initial_capacity(mut self, initial_capacity: usize) -> Self {
+        self.initial_capacity = initial_capacity;
+        self
+    }
+
+    /// The growth factor of the arena's [`GrowthPolicy`], 1 by default.
+    pub fn growth_factor(mut self, growth_factor: f64) -> Self {
+        self.growth_factor = growth_factor;
+        self
+    }
+
+    /// Whether chunks overwrite their used memory with zeros when they are reset or dropped. See [`SingleArena::new_zeroizing`].
+    pub fn zeroize(mut self, zeroize: bool) -> Self {
+        self.zeroize = zeroize;
+        self
+    }
+
+    /// Whether chunks fill free memory with a poison byte. See [`SingleArena::set_poisoning`].
+    pub fn poison(mut self, poison: bool) -> Self {
+        self.poison = poison;
+        self
+    }
+
+    /// Whether allocations are made in the first chunk with enough capacity, instead of only the last chunk. See [`Arena::set_first_fit`].
+    pub fn first_fit(mut self, first_fit: bool) -> Self {
+        self.first_fit = first_fit;
+        self
+    }
+
+    /// The maximum total size of the arena's chunks, with no limit by default. See [`Arena::set_max_total_bytes`].
+    pub fn max_total_bytes(mut self, max_total_bytes: usize) -> Self {
+        self.max_total_bytes = max_total_bytes;
+        self
+    }
+
+    /// Create the arena, checking that the settings are valid.
+    pub fn build(self) -> Result<Arena, ArenaBuilderError> {
+        if self.chunk_size == 0 || self.chunk_size > isize::MAX as usize {
+            return Err(ArenaBuilderError::InvalidChunkSize)
+        }
+        if self.initial_capacity > isize::MAX as usize {
+            return Err(ArenaBuilderError::InvalidInitialCapacity)
+        }
+        if !self.growth_factor.is_finite() || self.growth_factor < 1.0 {
+            return Err(ArenaBuilderError::InvalidGrowthFactor)
+        }
+        if self.initial_capacity > self.max_total_bytes {
+            return Err(ArenaBuilderError::InitialCapacityExceedsMaximum)
+        }
+
+        let mut arena = Arena::new();
+        arena.chunk_size = self.chunk_size;
+        arena.set_growth_policy(GrowthPolicy { factor: self.growth_factor, ..GrowthPolicy::default() });
+        arena.zeroizing = self.zeroize;
+        arena.poisoning = self.poison;
+        arena.first_fit = self.first_fit;
+        arena.set_max_total_bytes(self.max_total_bytes);
+
+        if self.initial_capacity != 0 {
+            // safety: the initial capacity has been checked
+            let mut chunk = unsafe { SingleArena::new_unchecked(self.initial_capacity) };
+            arena.configure_chunk(&mut chunk);
+            arena.chunks.push(chunk);
+        }
+        Ok(arena)
+    }
+}
+
+impl Default for ArenaBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArenaAllocator;
+
+    #[test]
+    fn settings_take_effect() {
+        let arena = ArenaBuilder::new()
+            .chunk_size(1000)
+            .initial_capacity(100)
+            .growth_factor(3.0)
+            .zeroize(true)
+            .poison(true)
+            .build()
+            .unwrap();
+        assert_eq!(arena.chunk_count(), 1);
+
+        let first = arena.chunks.last().unwrap();
+        assert_eq!(first.size(), 100);
+        assert!(first.is_zeroizing() && first.is_poisoning());
+
+        for _i in 0..200 {
+            let _ = arena.allocate(0u8);
+        }
+        // the second chunk is at least the chunk size
+        assert_eq!(arena.chunks.last().unwrap().size(), 1000);
+
+        for _i in 0..1000 {
+            let _ = arena.allocate(0u8);
+        }
+        assert_eq!(arena.chunks.last().unwrap().size(), 3000);
+        assert!(arena.chunks.last().unwrap().is_zeroizing());
+    }
+
+    #[test]
+    fn invalid_settings() {
+        assert_eq!(ArenaBuilder::new().chunk_size(0).build().err(), Some(ArenaBuilderError::InvalidChunkSize));
+        assert_eq!(ArenaBuilder::new().initial_capacity(usize::MAX).build().err(), Some(ArenaBuilderError::InvalidInitialCapacity));
+        assert_eq!(ArenaBuilder::new().growth_factor(0.5).build().err(), Some(ArenaBuilderError::InvalidGrowthFactor));
+        assert_eq!(ArenaBuilder::new().growth_factor(f64::NAN).build().err(), Some(ArenaBuilderError::InvalidGrowthFactor));
+        assert_eq!(
+            ArenaBuilder::new().initial_capacity(8192).max_total_bytes(4096).build().err(),
+            Some(ArenaBuilderError::InitialCapacityExceedsMaximum)
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use super::{Arena, ArenaAllocator, ArenaBox};
+
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::ptr::NonNull;
+
+// a pointer to an interned value in the arena, which hashes and compares as the value
+struct Interned<T>(NonNull<T>);
+
+impl<T> Interned<T> {
+    fn get(&self) -> &T {
+        // safety: the value is valid until the interner is dropped, and is never mutated
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<T: Hash> Hash for Interned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.get().hash(state)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Interned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Eq> Eq for Interned<T> {}
+
+// allows looking up an interned value by a T, without storing a second copy of it as the key
+impl<T> Borrow<T> for Interned<T> {
+    fn borrow(&self) -> &T {
+        self.get()
+    }
+}
+
+/// Deduplicates values by allocating each distinct value once in an [`Arena`], such as to intern strings or symbols.
+///
+/// Interning a value equal to one that was interned before returns a reference to the existing value,
+/// so equal interned values can be compared by pointer.
+///
+/// ```
+/// let interner = arena::ArenaInterner::new();
+/// let a = interner.intern(String::from("symbol"));
+/// let b = interner.intern(String::from("symbol"));
+/// assert!(std::ptr::eq(a, b));
+/// ```
+pub struct ArenaInterner<T: Hash + Eq> {
+    // points into the chunks of `arena`, which don't move when the interner is moved
+    values: RefCell<HashSet<Interned<T>>>,
+    arena: Arena
+}
+
+impl<T: Hash + Eq> ArenaInterner<T> {
+    pub fn new() -> Self {
+        Self::from_arena(Arena::new())
+    }
+
+    /// Create an interner which allocates its values in `arena`, such as an arena with a chosen chunk size.
+    pub fn from_arena(arena: Arena) -> Self {
+        Self { values: RefCell::new(HashSet::new()), arena }
+    }
+
+    /// Return a reference to the interned value equal to `value`, allocating `value` in the arena if there isn't one.
+    ///
+    /// The reference is valid for as long as the interner.
+    pub fn intern(&self, value: T) -> &T {
+        let mut values = self.values.borrow_mut();
+        if let Some(interned) = values.get(&value) {
+            // safety: the value lives as long as the interner, which `&self` borrows
+            return unsafe { interned.0.as_ref() }
+        }
+
+        // the allocation is freed when the interner is dropped
+        let (ptr, _) = ArenaBox::into_raw(self.arena.allocate(value));
+        values.insert(Interned(ptr));
+        unsafe { ptr.as_ref() }
+    }
+
+    /// Return the interned value equal to `value`, if there is one.
+    pub fn get(&self, value: &T) -> Option<&T> {
+        let values = self.values.borrow();
+        // safety: the value lives as long as the interner, which `&self` borrows
+        values.get(value).map(|interned| unsafe { interned.0.as_ref() })
+    }
+
+    /// The number of distinct values interned.
+    pub fn len(&self) -> usize {
+        self.values.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Hash + Eq> Default for ArenaInterner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq> Drop for ArenaInterner<T> {
+    fn drop(&mut self) {
+        for interned in self.values.get_mut().drain() {
+            // safety: each value was allocated once by `intern`, and no references to it outlive the interner
+            unsafe { std::ptr::drop_in_place(interned.0.as_ptr()) };
+        }
+        // the boxes were leaked by `intern`, so the chunks still count the allocations
+        self.arena.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_duplicate_strings() {
+        let interner = ArenaInterner::new();
+        let words = ["apple", "pear", "apple", "plum", "pear", "apple"];
+        let interned: Vec<&String> = words.iter().map(|word| interner.intern(word.to_string())).collect();
+
+        assert_eq!(interner.len(), 3);
+        assert!(std::ptr::eq(interned[0], interned[2]));
+        assert!(std::ptr::eq(interned[0], interned[5]));
+        assert!(std::ptr::eq(interned[1], interned[4]));
+        assert!(!std::ptr::eq(interned[0], interned[1]));
+        assert_eq!(interned[3], "plum");
+        assert!(std::ptr::eq(interner.get(&String::from("plum")).unwrap(), interned[3]));
+        assert!(interner.get(&String::from("fig")).is_none());
+    }
+
+    #[test]
+    fn interned_values_dropped() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        {
+            let interner = ArenaInterner::new();
+            for i in 0..10 {
+                interner.intern((i % 3, Rc::clone(&counter)));
+            }
+            // the duplicates were dropped by `intern`
+            assert_eq!(Rc::strong_count(&counter), 4);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+}
@@ -0,0 +1,97 @@
+use std::ops::{Index, IndexMut};
+
+use super::{ArenaBox, ArenaChunk};
+
+/// A slice allocated in an arena, such as by [`ArenaChunk::allocate_slice`], with bounds checked indexing.
+///
+/// This is a wrapper around an `ArenaBox<[T]>`, which can be converted to and from it.
+pub struct ArenaSlice<'a, T, A: ArenaChunk> {
+    inner: ArenaBox<'a, [T], A>
+}
+
+impl<'a, T, A: ArenaChunk> ArenaSlice<'a, T, A> {
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the element at `index`, or None if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.inner.get(index)
+    }
+
+    /// Return the element at `index` mutably, or None if it is out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.inner.get_mut(index)
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.inner
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.inner
+    }
+
+    /// Return the box of the slice.
+    pub fn into_box(self) -> ArenaBox<'a, [T], A> {
+        self.inner
+    }
+}
+
+impl<'a, T, A: ArenaChunk> From<ArenaBox<'a, [T], A>> for ArenaSlice<'a, T, A> {
+    fn from(inner: ArenaBox<'a, [T], A>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, T, A: ArenaChunk> Index<usize> for ArenaSlice<'a, T, A> {
+    type Output = T;
+
+    /// # Panics
+    /// If `index` is out of bounds.
+    fn index(&self, index: usize) -> &T {
+        let len = self.len();
+        self.get(index).unwrap_or_else(|| panic!("index {index} is out of bounds for an ArenaSlice of length {len}"))
+    }
+}
+
+impl<'a, T, A: ArenaChunk> IndexMut<usize> for ArenaSlice<'a, T, A> {
+    /// # Panics
+    /// If `index` is out of bounds.
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len();
+        self.get_mut(index).unwrap_or_else(|| panic!("index {index} is out of bounds for an ArenaSlice of length {len}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::single_chunk::SingleArena;
+
+    #[test]
+    fn in_bounds_access() {
+        let arena = SingleArena::new(100).unwrap();
+        let mut slice = arena.allocate_slice(&[1u32, 2, 3]).unwrap();
+        assert_eq!(slice.len(), 3);
+
+        slice[1] = 20;
+        *slice.get_mut(2).unwrap() += 10;
+        assert_eq!(slice[0], 1);
+        assert_eq!(slice.as_slice(), [1, 20, 13]);
+        assert_eq!(slice.get(3), None);
+        assert!(slice.get_mut(10).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "index 3 is out of bounds for an ArenaSlice of length 3")]
+    fn out_of_bounds_panics() {
+        let arena = SingleArena::new(100).unwrap();
+        let slice = arena.allocate_slice(&[1u32, 2, 3]).unwrap();
+        let _ = slice[3];
+    }
+}
@@ -1,28 +1,92 @@
 use std::alloc::Layout;
-use std::mem::{size_of, align_of};
+use std::mem::{size_of, align_of, MaybeUninit};
 use std::alloc;
 use std::ptr::NonNull;
+use std::pin::Pin;
 
-use super::ArenaBox;
+use super::{ArenaBox, ArenaSlice};
 
 /// A simple arena based allocator, which uses a linked list of chunks of memory.
 pub trait ArenaAllocator<C: ArenaChunk> {
     fn new() -> Self;
-    fn allocate<T>(&self, object: T) -> ArenaBox<T, C>;
+    fn allocate<T>(&self, object: T) -> ArenaBox<'_, T, C>;
 }
 
+/// Arenas and chunks which objects can be allocated in through [`allocate_in`], whichever allocation method they have.
+pub trait AllocateIn {
+    /// The chunk type of the boxes allocated in the arena.
+    type Chunk: ArenaChunk;
+
+    /// Allocate an object in the arena. Return None if the arena doesn't have the capacity for the object.
+    fn allocate_in<T>(&self, object: T) -> Option<ArenaBox<'_, T, Self::Chunk>>;
+}
+
+impl<A: ArenaChunk> AllocateIn for A {
+    type Chunk = A;
+
+    fn allocate_in<T>(&self, object: T) -> Option<ArenaBox<'_, T, A>> {
+        self.allocate(object)
+    }
+}
+
+/// Allocate an object in any arena or chunk, such as in a container which is generic over its arena.
+/// 
+/// Return None if the arena doesn't have the capacity for the object.
+/// 
+/// ```
+/// use arena::{allocate_in, Arena, ArenaAllocator, ArenaChunk, single_chunk::SingleArena};
+/// 
+/// let chunk = SingleArena::new(64).unwrap();
+/// let arena = Arena::new();
+/// assert_eq!(*allocate_in(&chunk, 5).unwrap(), 5);
+/// assert_eq!(*allocate_in(&arena, 6).unwrap(), 6);
+/// ```
+pub fn allocate_in<T, A: AllocateIn>(arena: &A, object: T) -> Option<ArenaBox<'_, T, A::Chunk>> {
+    arena.allocate_in(object)
+}
+
+/// Marks types which can be leaked in an arena without leaking resources, as they have no destructor that must run,
+/// for use with [`ArenaChunk::allocate_safe`].
+/// 
+/// It is implemented for every `Copy` type, and can be implemented for other types without a destructor.
+/// 
+/// ```
+/// use arena::{ArenaChunk, ArenaSafe, single_chunk::SingleArena};
+/// 
+/// struct Point<'a> { x: &'a mut i32 }
+/// impl ArenaSafe for Point<'_> {}
+/// 
+/// let chunk = SingleArena::new(64).unwrap();
+/// let number = chunk.allocate_safe(5u32).unwrap();
+/// let mut x = 1;
+/// let point = chunk.allocate_safe(Point { x: &mut x }).unwrap();
+/// # drop((number, point));
+/// ```
+/// 
+/// ```compile_fail
+/// use arena::{ArenaChunk, single_chunk::SingleArena};
+/// 
+/// let chunk = SingleArena::new(64).unwrap();
+/// let string = chunk.allocate_safe(String::from("owns a heap allocation"));
+/// ```
+pub trait ArenaSafe {}
+
+impl<T: Copy> ArenaSafe for T {}
+
 /// Objects implementing this trait can be used as a 'chunk' or 'block' in arena allocators
 pub trait ArenaChunk: Sized {
     /// Create a new chunk without checking whether the size is valid
     /// 
     /// # Safety
-    /// Can cause UB if size is 0
+    /// Can cause UB if size is 0 or greater than isize::MAX
     unsafe fn new_unchecked(size: usize) -> Self;
 
     /// Allocate an object in the chunk.
     /// 
+    /// Zero sized types don't use memory in the chunk, so they aren't counted as allocations.
+    /// 
     /// Return None if the chunk doesn't have the capacity for the object.
-    fn allocate<T>(&self, object: T) -> Option<ArenaBox<T, Self>>;
+    fn allocate<T>(&self, object: T) -> Option<ArenaBox<'_, T, Self>>;
 
     /// Return a pointer to the start of the arena's memory.
     fn get_start_pointer_mut(&self) -> *mut u8;
@@ -36,19 +100,82 @@ pub trait ArenaChunk: Sized {
     /// UB if the pointer is set outside of the arena, overwrites allocated objects, or is a null/invalid pointer.
     unsafe fn set_free_pointer(&self, ptr: *mut u8);
 
+    /// Move the free pointer forward to a new pointer, skipping free memory, if it is between the free pointer and the end of the chunk inclusive.
+    /// 
+    /// Return whether the pointer was set. The free pointer is unchanged if it wasn't, such as if the pointer is
+    /// outside of the chunk, or before the free pointer, where it would overwrite allocated objects.
+    /// To free memory, use a method which checks the allocations, like [`SingleArena::rewind`](crate::single_chunk::SingleArena::rewind).
+    // the pointer is only compared and stored, never dereferenced
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn try_set_free_pointer(&self, ptr: *mut u8) -> bool {
+        let end = self.get_start_pointer_mut() as usize + self.size();
+        if (ptr as usize) < self.get_free_pointer_mut() as usize || ptr as usize > end {
+            return false
+        }
+
+        // safety: the pointer is in the chunk's free memory, so no allocations are overwritten
+        unsafe { self.set_free_pointer(ptr) };
+        true
+    }
+
     /// The remaining capacity of the chunk in bytes.
     fn remaining_capacity(&self) -> usize;
 
-    /// Adjust a counter of the number of allocations in the arena chunk.
+    /// The number of bytes of the chunk that have been used, including alignment padding.
+    fn used_capacity(&self) -> usize {
+        // remaining capacity is never greater than the size, but saturate in case an implementation is wrong
+        self.size().saturating_sub(self.remaining_capacity())
+    }
+
+    /// The number of Ts that could be allocated in the chunk, accounting for alignment padding.
+    /// 
+    /// Returns usize::MAX for zero sized types, as they don't use memory in the chunk.
+    fn capacity_for<T>(&self) -> usize {
+        // size is always a multiple of the alignment, so it is the stride between consecutive Ts
+        let allocation_size = size_of::<T>();
+        if allocation_size == 0 {
+            return usize::MAX
+        }
+
+        let offset = self.get_free_pointer_mut().align_offset(align_of::<T>());
+        self.remaining_capacity().saturating_sub(offset) / allocation_size
+    }
+
+    /// Adjust a counter of the number of allocations in the arena chunk, by a signed amount.
     /// 
     /// This is handled in the allocation methods and when allocations are dropped.
+    /// Implementations should panic if the count overflows, or underflows below 0, which means that an allocation was freed twice.
+    /// [`adjusted_allocation_count`] does this.
     fn adjust_allocation_count(&self, count: isize);
 
+    /// Notify the chunk that the allocation starting at `ptr` is unused, once its destructor has run, such as when its `ArenaBox` is dropped.
+    /// 
+    /// By default this decrements the allocation count, and the memory isn't reused until the chunk is reset.
+    /// 
+    /// # Safety
+    /// `ptr` must be the start of an allocation in this chunk which hasn't been freed, and isn't used afterwards.
+    unsafe fn free_allocation(&self, ptr: *mut u8) {
+        let _ = ptr;
+        self.adjust_allocation_count(-1)
+    }
+
+    /// Record that an allocation has no box to free it, such as from [`alloc_mut`](ArenaChunk::alloc_mut),
+    /// so it stays counted until the chunk is reset. Chunks which check for leaks when dropped don't count these as leaks.
+    /// 
+    /// By default this does nothing.
+    fn record_unowned_allocation(&self) {}
+
+    /// Record that `bytes` bytes were skipped to align an allocation, such as to diagnose alignment waste.
+    /// 
+    /// This is called by [`write_to_memory`](ArenaChunk::write_to_memory), and by default does nothing.
+    fn record_alignment_padding(&self, _bytes: usize) {}
+
     fn size(&self) -> usize;
 
-    /// Create a new chunk, checking that size is greater than 0
+    /// Create a new chunk, checking that size is greater than 0 and not greater than isize::MAX
     fn new(size: usize) -> Option<Self> {
-        if size == 0 {
+        // the checked layout constructor rejects sizes greater than isize::MAX
+        if size == 0 || Layout::from_size_align(size, 1).is_err() {
             None
         } else {
             Some(unsafe { Self::new_unchecked(size) })
@@ -60,16 +187,480 @@ pub trait ArenaChunk: Sized {
     /// Aborts process in an allocation error.
     /// 
     /// # Safety
-    /// UB if size is 0.
+    /// UB if size is 0 or greater than isize::MAX.
     unsafe fn intialise_chunk(size: usize) -> *mut u8 {
+        match Self::try_intialise_chunk(size) {
+            Some(ptr) => ptr,
+            None => alloc::handle_alloc_error(Layout::from_size_align_unchecked(size, 1))
+        }
+    }
+
+    /// Allocate the memory needed for this chunk and return a pointer to the start of the allocation,
+    /// or None if the allocation fails, instead of aborting like [`intialise_chunk`](ArenaChunk::intialise_chunk).
+    /// 
+    /// # Safety
+    /// UB if size is 0 or greater than isize::MAX.
+    unsafe fn try_intialise_chunk(size: usize) -> Option<*mut u8> {
         // safety: align of one byte means that none of the checks are necessary
-        // CAN BE UNSAFE IF SIZE IS 0
-        let layout = Layout::from_size_align_unchecked(size, 1);
+        // CAN BE UNSAFE IF SIZE IS 0 OR GREATER THAN isize::MAX
+        Self::try_intialise_chunk_aligned(size, 1)
+    }
+
+    /// Allocate the memory needed for this chunk aligned to `align`, like [`try_intialise_chunk`](ArenaChunk::try_intialise_chunk).
+    /// The chunk's [`alignment`](ArenaChunk::alignment) must return `align`, so that it is deallocated with the same layout.
+    /// 
+    /// # Safety
+    /// UB if size is 0, `align` isn't a power of two, or size rounded up to `align` is greater than isize::MAX.
+    unsafe fn try_intialise_chunk_aligned(size: usize, align: usize) -> Option<*mut u8> {
+        let layout = Layout::from_size_align_unchecked(size, align);
         let ptr = alloc::alloc(layout);
-        if ptr.is_null() {
-            alloc::handle_alloc_error(layout)
+        (!ptr.is_null()).then_some(ptr)
+    }
+
+    /// The alignment that the chunk's memory was allocated with, which is 1 unless the chunk was created with a larger alignment.
+    fn alignment(&self) -> usize {
+        1
+    }
+
+    /// Reserve `size` bytes of memory aligned to `align`, by moving the free pointer past them.
+    /// 
+    /// Return a pointer to the start of the memory, or None if the chunk doesn't have the capacity.
+    /// The memory is uninitialised, and the allocation count isn't adjusted.
+    /// 
+    /// `align` must be a power of two.
+    fn reserve_bytes(&self, size: usize, align: usize) -> Option<*mut u8> {
+        let offset = self.get_free_pointer_mut().align_offset(align);
+
+        // checks that there is enough free space to reserve the memory
+        if size.checked_add(offset)? > self.remaining_capacity() {
+            return None
+        }
+
+        unsafe {
+            // safety: there is enough remaining capacity for the offset and the memory
+            let ptr = self.get_free_pointer_mut().add(offset);
+            self.set_free_pointer(ptr.add(size));
+            Some(ptr)
+        }
+    }
+
+    /// Allocate an object in the chunk, like `allocate`, but only for [`ArenaSafe`] types, which don't leak resources if their box is leaked.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the object.
+    fn allocate_safe<T: ArenaSafe>(&self, object: T) -> Option<ArenaBox<'_, T, Self>> {
+        self.allocate(object)
+    }
+
+    /// Reserve memory for `layout` in the chunk, like [`reserve_bytes`](ArenaChunk::reserve_bytes), such as for building other allocators.
+    /// 
+    /// The memory is uninitialised, and isn't counted as an allocation, so it is only freed when the chunk is reset.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the layout.
+    fn allocate_raw_bytes(&self, layout: Layout) -> Option<NonNull<u8>> {
+        // safety: reserved memory is in the chunk, so is non-null
+        self.reserve_bytes(layout.size(), layout.align()).map(|ptr| unsafe { NonNull::new_unchecked(ptr) })
+    }
+
+    /// Allocate an object in the chunk, constructing it with `f` directly in the chunk's memory.
+    /// 
+    /// This can avoid a large object being constructed on the stack and then moved into the chunk.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the object, in which case `f` isn't called.
+    fn allocate_with<T, F: FnOnce() -> T>(&self, f: F) -> Option<ArenaBox<'_, T, Self>> {
+        let allocation_size = size_of::<T>();
+
+        // handle zst
+        if allocation_size == 0 {
+            // the box owns the zero sized value
+            std::mem::forget(f());
+            return Some(ArenaBox::new_zero_sized())
+        }
+
+        // reserve the slot before calling f, so allocations made by f can't overlap it.
+        // if f panics, the slot is left as unused memory and the allocation count is unchanged
+        let object_pointer = self.reserve_bytes(allocation_size, align_of::<T>())?.cast::<T>();
+
+        unsafe {
+            std::ptr::write(object_pointer, f());
+            self.adjust_allocation_count(1);
+
+            // safety: object pointer is non-null
+            Some(ArenaBox::new(self, NonNull::new_unchecked(object_pointer)))
+        }
+    }
+
+    /// Allocate an object in the chunk, constructing it with a fallible `f` directly in the chunk's memory, such as when parsing.
+    /// 
+    /// If `f` returns an error, the reservation is rolled back and the error is returned. The reservation can only be rolled back
+    /// if nothing was allocated in the chunk by `f`, otherwise its slot is left as unused memory.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the object, in which case `f` isn't called.
+    fn allocate_try_with<T, E, F: FnOnce() -> Result<T, E>>(&self, f: F) -> Option<Result<ArenaBox<'_, T, Self>, E>> {
+        let allocation_size = size_of::<T>();
+
+        // handle zst
+        if allocation_size == 0 {
+            return Some(f().map(|object| {
+                // the box owns the zero sized value
+                std::mem::forget(object);
+                ArenaBox::new_zero_sized()
+            }))
+        }
+
+        // reserve the slot before calling f, so allocations made by f can't overlap it
+        let old_free_pointer = self.get_free_pointer_mut();
+        let object_pointer = self.reserve_bytes(allocation_size, align_of::<T>())?.cast::<T>();
+        let reserved_free_pointer = self.get_free_pointer_mut();
+
+        match f() {
+            Ok(object) => unsafe {
+                std::ptr::write(object_pointer, object);
+                self.adjust_allocation_count(1);
+
+                // safety: object pointer is non-null
+                Some(Ok(ArenaBox::new(self, NonNull::new_unchecked(object_pointer))))
+            },
+            Err(error) => {
+                if self.get_free_pointer_mut() == reserved_free_pointer {
+                    // safety: nothing has been allocated since the slot was reserved, so the slot is the only memory freed
+                    unsafe { self.set_free_pointer(old_free_pointer) };
+                }
+                Some(Err(error))
+            }
+        }
+    }
+
+    /// Allocate an object in the chunk, aligned to at least `align` bytes, such as to a page or DMA boundary.
+    /// 
+    /// The object is aligned to the greater of `align` and the alignment of T. Zero sized types aren't allocated in the chunk,
+    /// so their pointer is only aligned for T.
+    /// 
+    /// Return None if `align` isn't a power of two, or the chunk doesn't have the capacity for the object.
+    fn allocate_aligned<T>(&self, object: T, align: usize) -> Option<ArenaBox<'_, T, Self>> {
+        if !align.is_power_of_two() {
+            return None
+        }
+
+        let allocation_size = size_of::<T>();
+
+        // handle zst
+        if allocation_size == 0 {
+            // the box owns the zero sized value
+            std::mem::forget(object);
+            return Some(ArenaBox::new_zero_sized())
+        }
+
+        let object_pointer = self.reserve_bytes(allocation_size, std::cmp::max(align_of::<T>(), align))?.cast::<T>();
+
+        unsafe {
+            std::ptr::write(object_pointer, object);
+            self.adjust_allocation_count(1);
+
+            // safety: object pointer is non-null
+            Some(ArenaBox::new(self, NonNull::new_unchecked(object_pointer)))
+        }
+    }
+
+    /// Allocate a copy of `values` in the chunk, cloning each element, as an [`ArenaSlice`] with bounds checked indexing.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the slice.
+    fn allocate_slice<T: Clone>(&self, values: &[T]) -> Option<ArenaSlice<'_, T, Self>> {
+        allocate_slice_with(self, values.len(), |i| values[i].clone()).map(ArenaSlice::from)
+    }
+
+    /// Allocate a slice of `len` Ts, where each element is the result of calling `f` with its index, such as to build a lookup table.
+    /// 
+    /// The memory for the slice is reserved once. If `f` panics, the elements already written are dropped.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the slice, in which case `f` isn't called.
+    fn allocate_slice_with<T, F: FnMut(usize) -> T>(&self, len: usize, f: F) -> Option<ArenaBox<'_, [T], Self>> {
+        allocate_slice_with(self, len, f)
+    }
+
+    /// Allocate a column of `count` Ts, filled with `T::default()`, to be written to by index.
+    /// 
+    /// Allocating each field of a struct as a separate column (a struct-of-arrays layout)
+    /// makes iterating over a single field cache friendly. Each column is aligned for its type.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the column.
+    fn allocate_columns<T: Default>(&self, count: usize) -> Option<ArenaBox<'_, [T], Self>> {
+        allocate_slice_with(self, count, |_| T::default())
+    }
+
+    /// Reserve a slice of `len` uninitialised Ts in the chunk, to be written to one by one,
+    /// then converted with [`ArenaBox::assume_init`] once every element is written.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the slice.
+    fn allocate_uninit_slice<T>(&self, len: usize) -> Option<ArenaBox<'_, [MaybeUninit<T>], Self>> {
+        // handle zst
+        if size_of::<T>() == 0 {
+            // safety: MaybeUninit doesn't need to be initialised or dropped
+            return Some(unsafe { ArenaBox::new_zero_sized_slice(len) })
+        }
+
+        if len == 0 {
+            return Some(ArenaBox::new_empty_slice())
+        }
+
+        let slice_pointer = self.reserve_bytes(slice_size::<T>(len)?, align_of::<T>())?.cast::<MaybeUninit<T>>();
+        self.adjust_allocation_count(1);
+
+        // safety: slice pointer is non-null, and MaybeUninit doesn't need to be initialised
+        unsafe { Some(ArenaBox::new(self, NonNull::slice_from_raw_parts(NonNull::new_unchecked(slice_pointer), len))) }
+    }
+
+    /// Allocate the items of an iterator as a contiguous slice in the chunk.
+    /// 
+    /// The number of items isn't known beforehand, so they are collected into a temporary `Vec` first.
+    /// Use [`allocate_iter_exact`](ArenaChunk::allocate_iter_exact) for an `ExactSizeIterator` to avoid this.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the slice.
+    fn allocate_iter<T, I: IntoIterator<Item = T>>(&self, iter: I) -> Option<ArenaBox<'_, [T], Self>> {
+        let items: Vec<T> = iter.into_iter().collect();
+        self.allocate_iter_exact(items)
+    }
+
+    /// Allocate the items of an `ExactSizeIterator` as a contiguous slice in the chunk, reserving the memory for them up front.
+    /// 
+    /// If the iterator yields fewer items than its length, the slice only contains the items that were yielded.
+    /// If it yields more, the extra items aren't consumed.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the slice.
+    fn allocate_iter_exact<T, I>(&self, iter: I) -> Option<ArenaBox<'_, [T], Self>>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator
+    {
+        let mut iter = iter.into_iter();
+        let len = iter.len();
+
+        if len == 0 {
+            return Some(ArenaBox::new_empty_slice())
+        }
+
+        // handle zst
+        if size_of::<T>() == 0 {
+            let mut yielded = 0;
+            for item in iter.by_ref().take(len) {
+                std::mem::forget(item);
+                yielded += 1;
+            }
+            // safety: the zero sized Ts have been forgotten, so the box owns them
+            return Some(unsafe { ArenaBox::new_zero_sized_slice(yielded) })
+        }
+
+        let slice_size = slice_size::<T>(len)?;
+        let slice_pointer = self.reserve_bytes(slice_size, align_of::<T>())?.cast::<T>();
+        self.adjust_allocation_count(1);
+
+        unsafe {
+            // drops the written elements if the iterator panics
+            let mut guard = PartialSliceGuard { arena: self, start: slice_pointer, initialised: 0 };
+            while guard.initialised < len {
+                match iter.next() {
+                    Some(item) => std::ptr::write(slice_pointer.add(guard.initialised), item),
+                    None => break
+                }
+                guard.initialised += 1;
+            }
+            let initialised = guard.initialised;
+            std::mem::forget(guard);
+
+            // safety: slice pointer is non-null, and the first `initialised` elements are initialised
+            let slice = NonNull::slice_from_raw_parts(NonNull::new_unchecked(slice_pointer), initialised);
+            Some(ArenaBox::new(self, slice))
+        }
+    }
+
+    /// Move the elements of a `Vec` into a contiguous slice in the chunk, and free the `Vec`'s buffer.
+    /// 
+    /// The elements are moved without being dropped or cloned.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the slice, in which case the `Vec` is dropped.
+    fn allocate_vec<T>(&self, mut vec: Vec<T>) -> Option<ArenaBox<'_, [T], Self>> {
+        let len = vec.len();
+
+        if len == 0 {
+            return Some(ArenaBox::new_empty_slice())
+        }
+
+        // handle zst
+        if size_of::<T>() == 0 {
+            // safety: the box takes ownership of the elements, so the vec must not drop them
+            unsafe { vec.set_len(0) };
+            return Some(unsafe { ArenaBox::new_zero_sized_slice(len) })
+        }
+
+        let slice_pointer = self.reserve_bytes(slice_size::<T>(len)?, align_of::<T>())?.cast::<T>();
+        self.adjust_allocation_count(1);
+
+        unsafe {
+            // safety: the reserved memory is valid for `len` Ts, and can't overlap with the vec's buffer.
+            // the elements are owned by the slice once they are copied, so the vec mustn't drop them
+            std::ptr::copy_nonoverlapping(vec.as_ptr(), slice_pointer, len);
+            vec.set_len(0);
+            drop(vec);
+
+            Some(ArenaBox::new(self, NonNull::slice_from_raw_parts(NonNull::new_unchecked(slice_pointer), len)))
+        }
+    }
+
+    /// Allocate an object in the chunk, and pin it.
+    /// 
+    /// Objects in a chunk are never moved, so the address is stable until the box is dropped.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the object.
+    /// 
+    /// # Safety
+    /// The box must not be leaked (e.g. with `mem::forget`), as the memory would be freed or reused
+    /// without the object being dropped, which breaks the drop guarantee of `Pin`.
+    unsafe fn allocate_pinned<T>(&self, object: T) -> Option<Pin<ArenaBox<'_, T, Self>>> {
+        // safety: the object is never moved out of the chunk while it is pinned
+        self.allocate(object).map(|arena_box| Pin::new_unchecked(arena_box))
+    }
+
+    /// Allocate an object in the chunk, and return a box of an unsized type such as a trait object,
+    /// using `coerce` to perform the coercion, e.g. `arena.allocate_dyn(object, |value| value as &mut dyn Display)`.
+    /// 
+    /// See [`ArenaBox::unsize`]. Return None if the chunk doesn't have the capacity for the object.
+    fn allocate_dyn<T, U: ?Sized>(&self, object: T, coerce: impl for<'r> FnOnce(&'r mut T) -> &'r mut U) -> Option<ArenaBox<'_, U, Self>> {
+        self.allocate(object).map(|arena_box| ArenaBox::unsize(arena_box, coerce))
+    }
+
+    /// Allocate a closure in the chunk, and return a callable box of it as a `dyn FnMut()`, such as for storing different callbacks together.
+    /// 
+    /// The closure can borrow anything which outlives the borrow of the chunk. It is called with `(*arena_box)()`,
+    /// and its destructor runs when the box is dropped.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the closure.
+    fn allocate_fn<'a, F: FnMut() + 'a>(&'a self, f: F) -> Option<ArenaBox<'a, dyn FnMut() + 'a, Self>> {
+        self.allocate_dyn(f, |f| f as &mut (dyn FnMut() + 'a))
+    }
+
+    /// Allocate an object in the chunk, and return a mutable reference to it instead of an `ArenaBox`, such as for scratch values.
+    /// 
+    /// **The object is never dropped**, as there is no box to drop it, so this is best for `Copy` types, or types without a destructor.
+    /// The allocation stays counted until the chunk is reset, which frees its memory to be reused,
+    /// but it isn't a leak, so the chunk can be dropped without resetting it, even with the `leak_check` feature.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the object.
+    // each call returns a reference to a new allocation, so the references don't alias
+    #[allow(clippy::mut_from_ref)]
+    fn alloc_mut<T>(&self, object: T) -> Option<&mut T> {
+        // handle zst
+        if size_of::<T>() == 0 {
+            std::mem::forget(object);
+            // safety: a dangling pointer is valid for zero sized types
+            return Some(unsafe { &mut *NonNull::dangling().as_ptr() })
+        }
+
+        let object_pointer = self.reserve_bytes(size_of::<T>(), align_of::<T>())?.cast::<T>();
+        // counted so that the memory isn't freed while the reference is borrowed, such as by `SingleArena::free_all`
+        self.adjust_allocation_count(1);
+        self.record_unowned_allocation();
+        unsafe {
+            // safety: the memory is reserved for the object, and borrowed for as long as the chunk
+            std::ptr::write(object_pointer, object);
+            Some(&mut *object_pointer)
+        }
+    }
+
+    /// Allocate an object in the chunk, and return a raw pointer to it instead of an `ArenaBox`, for FFI.
+    /// 
+    /// The allocation is counted until it is passed to [`free_raw`](ArenaChunk::free_raw).
+    /// The caller must make sure that the pointer isn't used after the chunk is dropped.
+    /// 
+    /// Return None if the chunk doesn't have the capacity for the object.
+    fn allocate_raw<T>(&self, object: T) -> Option<*mut T> {
+        let mut arena_box = self.allocate(object)?;
+        // safety: the box is forgotten, so the pointer is valid until the chunk is dropped
+        let ptr = unsafe { ArenaBox::mut_ptr(&mut arena_box) };
+        std::mem::forget(arena_box);
+        Some(ptr)
+    }
+
+    /// Run the destructor of an object allocated with [`allocate_raw`](ArenaChunk::allocate_raw), and notify the chunk that the allocation is unused.
+    /// 
+    /// # Safety
+    /// The pointer must have been returned by `allocate_raw` on this chunk, must not have been freed already, and must not be used afterwards.
+    unsafe fn free_raw<T>(&self, ptr: *mut T) {
+        std::ptr::drop_in_place(ptr);
+        // zero sized types aren't counted
+        if size_of::<T>() != 0 {
+            self.free_allocation(ptr.cast::<u8>());
+        }
+    }
+
+    /// Grow an allocated object into a slice of `new_len` elements, where the object is the first element,
+    /// and the other elements are `T::default()`.
+    /// 
+    /// If the object is the last allocation in the chunk and there is enough remaining capacity, the slice extends it in place.
+    /// Otherwise a new region is allocated and the object is moved to it, leaving the old slot as unused memory.
+    /// 
+    /// The original box is returned if `new_len` is 0, or the chunk doesn't have the capacity for the slice.
+    fn try_reallocate<'a, T: Default>(&'a self, old: ArenaBox<'a, T, Self>, new_len: usize) -> Result<ArenaBox<'a, [T], Self>, ArenaBox<'a, T, Self>> {
+        let allocation_size = size_of::<T>();
+
+        if new_len == 0 {
+            return Err(old)
+        }
+
+        // handle zst
+        if allocation_size == 0 {
+            std::mem::forget(ArenaBox::into_inner(old));
+            for _ in 1..new_len {
+                std::mem::forget(T::default());
+            }
+            // safety: the zero sized Ts have been forgotten, so the box owns them
+            return Ok(unsafe { ArenaBox::new_zero_sized_slice(new_len) })
+        }
+
+        let slice_size = match slice_size::<T>(new_len) {
+            Some(size) => size,
+            None => return Err(old)
+        };
+        let extra_size = slice_size - allocation_size;
+
+        // safety: the pointer is only used if it's the last allocation in this chunk
+        let old_pointer = unsafe { ArenaBox::const_ptr(&old) }.cast_mut();
+        let free_pointer = self.get_free_pointer_mut();
+        let is_last = old_pointer as usize >= self.get_start_pointer_mut() as usize
+            && old_pointer as usize + allocation_size == free_pointer as usize;
+
+        let slice_pointer = if is_last && extra_size <= self.remaining_capacity() {
+            // the allocation count of the old box is kept for the slice
+            std::mem::forget(old);
+            // safety: there is enough remaining capacity for the extra elements
+            unsafe { self.set_free_pointer(free_pointer.add(extra_size)) };
+            old_pointer
+        } else {
+            let region = match self.reserve_bytes(slice_size, align_of::<T>()) {
+                Some(region) => region.cast::<T>(),
+                None => return Err(old)
+            };
+
+            unsafe {
+                self.adjust_allocation_count(1);
+
+                // decrements the allocation count of the old box's chunk
+                std::ptr::write(region, ArenaBox::into_inner(old));
+                region
+            }
+        };
+
+        unsafe {
+            // drops the initialised elements if T::default() panics
+            let mut guard = PartialSliceGuard { arena: self, start: slice_pointer, initialised: 1 };
+            while guard.initialised < new_len {
+                std::ptr::write(slice_pointer.add(guard.initialised), T::default());
+                guard.initialised += 1;
+            }
+            std::mem::forget(guard);
+
+            // safety: slice pointer is non-null, and all the elements are initialised
+            let slice = NonNull::slice_from_raw_parts(NonNull::new_unchecked(slice_pointer), new_len);
+            Ok(ArenaBox::new(self, slice))
         }
-        ptr
     }
 
     /// Allocate an object without checking:
@@ -80,7 +671,7 @@ pub trait ArenaChunk: Sized {
     /// 
     /// # Safety
     /// If the listed requirements aren't checked, it may result in UB.
-    unsafe fn allocate_unchecked<T>(&self, object: T) -> ArenaBox<T, Self> {
+    unsafe fn allocate_unchecked<T>(&self, object: T) -> ArenaBox<'_, T, Self> {
         let allocation_size = size_of::<T>();
         let offset = self.get_free_pointer_mut().align_offset(align_of::<T>());
         self.write_to_memory(object, allocation_size, offset)
@@ -92,12 +683,22 @@ pub trait ArenaChunk: Sized {
     /// 
     /// # Safety
     /// Free pointer + offset should be an aligned address for the object, and valid for writes.
-    unsafe fn write_to_memory<T>(&self, object: T, byte_size: usize, offset: usize) -> ArenaBox<T, Self> {
+    unsafe fn write_to_memory<T>(&self, object: T, byte_size: usize, offset: usize) -> ArenaBox<'_, T, Self> {
+        // catches misuse of `allocate_unchecked`, such as without enough remaining capacity
+        let end = byte_size.checked_add(offset).and_then(|size| (self.get_free_pointer_mut() as usize).checked_add(size));
+        debug_assert!(end.is_some(), "Allocation size overflowed");
+        debug_assert!(
+            end.is_some_and(|end| end <= self.get_start_pointer_mut() as usize + self.size()),
+            "Allocation is outside of the chunk"
+        );
+
         // write the object to memory at the free pointer
         // offset should make the allocation be aligned
         let object_pointer = self.get_free_pointer_mut().add(offset).cast::<T>();
         std::ptr::write(object_pointer, object);
 
+        self.record_alignment_padding(offset);
+
         self.set_free_pointer(self.get_free_pointer_mut().add(byte_size + offset));
 
         self.adjust_allocation_count(1);
@@ -111,10 +712,82 @@ pub trait ArenaChunk: Sized {
     /// # Safety
     /// UB if used after deallocated.
     unsafe fn deallocate_arena(&mut self) {
-        // safety: align of one byte means that none of the checks are necessary
-        let layout = Layout::from_size_align_unchecked(self.size(), 1);
+        // safety: the size and alignment were checked when the memory was allocated
+        let layout = Layout::from_size_align_unchecked(self.size(), self.alignment());
         // safety: memory in the arena will not have been deallocated, and layout is the same as size will not change
         // unsafe if the arena is dropped and attempted to be used again
         alloc::dealloc(self.get_start_pointer_mut(), layout);
     }
 }
+
+/// Return `current` adjusted by `count`, for implementing [`ArenaChunk::adjust_allocation_count`].
+/// 
+/// # Panics
+/// If the count overflows (too many allocations), or underflows below 0 (an allocation was freed twice).
+pub fn adjusted_allocation_count(current: usize, count: isize) -> usize {
+    match current.checked_add_signed(count) {
+        Some(adjusted) => adjusted,
+        None if count < 0 => panic!("Allocation count underflow (an allocation was freed more than once)"),
+        None => panic!("Allocation count overflow (too many allocations)")
+    }
+}
+
+/// The size in bytes of a slice of `len` Ts, or None if it overflows or is greater than isize::MAX,
+/// which is the largest size of an allocation.
+pub(crate) fn slice_size<T>(len: usize) -> Option<usize> {
+    size_of::<T>().checked_mul(len).filter(|size| *size <= isize::MAX as usize)
+}
+
+/// Allocate a slice of `len` elements in a chunk, where each element is the result of calling `f` with its index.
+/// 
+/// Return None if the chunk doesn't have the capacity for the slice.
+pub(crate) fn allocate_slice_with<T, A: ArenaChunk, F: FnMut(usize) -> T>(arena: &A, len: usize, mut f: F) -> Option<ArenaBox<'_, [T], A>> {
+    // handle zst
+    if size_of::<T>() == 0 {
+        for i in 0..len {
+            std::mem::forget(f(i));
+        }
+        // safety: the zero sized Ts have been forgotten, so the box owns them
+        return Some(unsafe { ArenaBox::new_zero_sized_slice(len) })
+    }
+
+    if len == 0 {
+        return Some(ArenaBox::new_empty_slice())
+    }
+
+    let slice_size = slice_size::<T>(len)?;
+    let slice_pointer = arena.reserve_bytes(slice_size, align_of::<T>())?.cast::<T>();
+    arena.adjust_allocation_count(1);
+
+    unsafe {
+        // drops the initialised elements if f panics
+        let mut guard = PartialSliceGuard { arena, start: slice_pointer, initialised: 0 };
+        while guard.initialised < len {
+            std::ptr::write(slice_pointer.add(guard.initialised), f(guard.initialised));
+            guard.initialised += 1;
+        }
+        std::mem::forget(guard);
+
+        // safety: slice pointer is non-null, and all the elements are initialised
+        let slice = NonNull::slice_from_raw_parts(NonNull::new_unchecked(slice_pointer), len);
+        Some(ArenaBox::new(arena, slice))
+    }
+}
+
+/// Drops the initialised elements of a slice being written to a chunk, and releases its allocation,
+/// if a panic occurs before the slice is complete.
+/// 
+/// Should be forgotten once every element is written.
+pub(crate) struct PartialSliceGuard<'a, T, A: ArenaChunk> {
+    pub(crate) arena: &'a A,
+    pub(crate) start: *mut T,
+    pub(crate) initialised: usize
+}
+
+impl<'a, T, A: ArenaChunk> Drop for PartialSliceGuard<'a, T, A> {
+    fn drop(&mut self) {
+        // safety: the first `initialised` elements have been written, and aren't owned by anything else
+        unsafe { std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(self.start, self.initialised)) };
+        self.arena.adjust_allocation_count(-1);
+    }
+}
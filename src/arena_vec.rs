@@ -0,0 +1,186 @@
+use std::{
+    ops::{Index, IndexMut}, ptr::NonNull, marker::PhantomData, mem::{size_of, align_of}
+};
+use super::ArenaChunk;
+use super::arena_trait::slice_size;
+
+/// A growable collection, which stores its elements contiguously in an arena chunk.
+///
+/// An arena can't reallocate memory, so when the vec runs out of capacity it allocates a new region with double the capacity,
+/// and copies the elements to it. The old region becomes unused memory in the arena, until the arena is dropped.
+/// As the capacity doubles, the unused regions add up to less than the current capacity.
+///
+/// The vec counts as a single allocation in the arena while it has a region allocated.
+pub struct ArenaVec<'a, T, A: ArenaChunk> {
+    inner: NonNull<T>,
+    len: usize,
+    capacity: usize,
+    arena: &'a A,
+    // arena vec owns the Ts
+    phantom: PhantomData<T>
+}
+
+impl<'a, T, A: ArenaChunk> ArenaVec<'a, T, A> {
+    /// Create an empty vec. No memory is allocated until an element is pushed.
+    pub fn new(arena: &'a A) -> Self {
+        // zero sized types never need to allocate
+        let capacity = if size_of::<T>() == 0 { usize::MAX } else { 0 };
+        Self { inner: NonNull::dangling(), len: 0, capacity, arena, phantom: PhantomData }
+    }
+
+    /// Create an empty vec, with capacity for at least `capacity` elements.
+    ///
+    /// Return None if the arena doesn't have the capacity for the elements.
+    pub fn with_capacity(arena: &'a A, capacity: usize) -> Option<Self> {
+        let mut vec = Self::new(arena);
+        if capacity > vec.capacity {
+            vec.grow(capacity)?;
+        }
+        Some(vec)
+    }
+
+    /// Append an element to the end of the vec.
+    ///
+    /// If the vec is full and the arena doesn't have the capacity to grow it, the element is returned.
+    pub fn push(&mut self, object: T) -> Result<(), T> {
+        if self.len == self.capacity && self.grow(std::cmp::max(4, self.capacity * 2)).is_none() {
+            return Err(object)
+        }
+
+        // safety: len is less than the capacity, so the pointer is inside the region
+        unsafe { std::ptr::write(self.inner.as_ptr().add(self.len), object) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove the last element from the vec and return it, or None if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None
+        }
+
+        self.len -= 1;
+        // safety: the element at len is initialised, and won't be read again as len has been decremented
+        unsafe { Some(std::ptr::read(self.inner.as_ptr().add(self.len))) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of elements the vec can hold without growing.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        // safety: the first len elements are initialised, and the pointer is aligned and non-null
+        unsafe { std::slice::from_raw_parts(self.inner.as_ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // safety: the first len elements are initialised, and the pointer is aligned and non-null
+        unsafe { std::slice::from_raw_parts_mut(self.inner.as_ptr(), self.len) }
+    }
+
+    /// Allocate a new region for `capacity` elements, and move the elements to it.
+    fn grow(&mut self, capacity: usize) -> Option<()> {
+        let byte_size = slice_size::<T>(capacity)?;
+        let region = self.arena.reserve_bytes(byte_size, align_of::<T>())?.cast::<T>();
+
+        unsafe {
+            // the new region was free memory, so doesn't overlap the old region
+            std::ptr::copy_nonoverlapping(self.inner.as_ptr(), region, self.len);
+
+            if self.capacity == 0 {
+                self.arena.adjust_allocation_count(1);
+            }
+
+            self.inner = NonNull::new_unchecked(region);
+        }
+        self.capacity = capacity;
+        Some(())
+    }
+}
+
+impl<'a, T, A: ArenaChunk> Index<usize> for ArenaVec<'a, T, A> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.as_slice()[index]
+    }
+}
+
+impl<'a, T, A: ArenaChunk> IndexMut<usize> for ArenaVec<'a, T, A> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.as_mut_slice()[index]
+    }
+}
+
+impl<'a, T, A: ArenaChunk> Drop for ArenaVec<'a, T, A> {
+    fn drop(&mut self) {
+        // call the destructors of the elements without deallocating the memory
+        // safety: the elements are initialised, and this is the only reference to them
+        unsafe { std::ptr::drop_in_place(self.as_mut_slice()) };
+
+        if self.capacity != 0 && size_of::<T>() != 0 {
+            self.arena.adjust_allocation_count(-1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::single_chunk::SingleArena;
+
+    #[test]
+    fn push_thousand_elements() {
+        let arena = SingleArena::new(16384).unwrap();
+        let mut vec = ArenaVec::new(&arena);
+
+        for i in 0..1000_u32 {
+            vec.push(i).unwrap();
+        }
+        assert_eq!(vec.len(), 1000);
+        assert_eq!(arena.allocations.get(), 1);
+
+        let slice = vec.as_slice();
+        let start = slice.as_ptr();
+        for (i, element) in slice.iter().enumerate() {
+            assert_eq!(*element, i as u32);
+            assert_eq!(element as *const u32, unsafe { start.add(i) });
+        }
+
+        assert_eq!(vec[500], 500);
+        assert_eq!(vec.pop(), Some(999));
+        assert_eq!(vec.len(), 999);
+
+        drop(vec);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn push_full_arena() {
+        let arena = SingleArena::new(16).unwrap();
+        let mut vec = ArenaVec::new(&arena);
+
+        for i in 0..4_u32 {
+            vec.push(i).unwrap();
+        }
+        // the vec can't grow to a capacity of 8
+        assert_eq!(vec.push(4), Err(4));
+        assert_eq!(vec.as_slice(), &[0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn capacity_overflow() {
+        let arena = SingleArena::new(16).unwrap();
+        assert!(ArenaVec::<u64, _>::with_capacity(&arena, usize::MAX / 8 + 1).is_none());
+        assert!(ArenaVec::<u64, _>::with_capacity(&arena, usize::MAX / 16 + 1).is_none());
+        assert_eq!(arena.allocations.get(), 0);
+    }
+}
@@ -2,7 +2,8 @@ use std::{collections::LinkedList, cell::UnsafeCell};
 
 /// This list allows references to elements in the list and pushing elements to the end of the list, with a shared reference.
 /// 
-/// This is allowed as a linked list ensures that the item references remain valid, and this list doesn't allow the removal or moving of items.
+/// This is allowed as a linked list ensures that the item references remain valid, and this list doesn't allow the removal or moving of items,
+/// except through a mutable reference, or unsafely with `split_off_shared`.
 /// 
 /// Used to store a list of memory blocks in an arena.
 pub(crate) struct UnshrinkableLinkedList<T> {
@@ -10,7 +11,7 @@ pub(crate) struct UnshrinkableLinkedList<T> {
 }
 
 impl<T> UnshrinkableLinkedList<T> {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Self { inner: UnsafeCell::new(LinkedList::new()) }
     }
 
@@ -22,13 +23,44 @@ impl<T> UnshrinkableLinkedList<T> {
     }
 
     /// Using this method may result in different items if the list is changed, using interior mutability.
-    #[allow(dead_code)]
     pub fn len(&self) -> usize {
         // safety: unsafe cell has a valid and dereferenceable pointer,
         // and no mutable references are released to the linked list
         unsafe { (*self.inner.get()).len() }
     }
 
+    /// Iterate over the items in the list, from first to last.
+    /// 
+    /// Items pushed while iterating aren't yielded.
+    pub fn iter(&self) -> std::collections::linked_list::Iter<'_, T> {
+        // safety: unsafe cell has a valid and dereferenceable pointer,
+        // and no mutable references are released to the linked list
+        unsafe { (*self.inner.get()).iter() }
+    }
+
+    /// Remove all the items from the list, which requires that there are no references to them.
+    pub fn take(&mut self) -> LinkedList<T> {
+        std::mem::take(self.inner.get_mut())
+    }
+
+    /// Move all the items of `other` to the end of the list.
+    pub fn append(&mut self, other: &mut LinkedList<T>) {
+        self.inner.get_mut().append(other)
+    }
+
+    /// Remove the last item from the list, which requires that there are no references to it.
+    pub fn pop(&mut self) -> Option<T> {
+        self.inner.get_mut().pop_back()
+    }
+
+    /// Remove the items from index `at` onward, with a shared reference.
+    /// 
+    /// # Safety
+    /// There must be no references to the removed items.
+    pub unsafe fn split_off_shared(&self, at: usize) -> LinkedList<T> {
+        (*self.inner.get()).split_off(at)
+    }
+
     pub fn push(&self, object: T) {
         // safety: only immutable references to this list are references to items in the list.
         // extending the list won't affect the immutable references
@@ -0,0 +1,167 @@
+use std::cell::Cell;
+use std::mem::{size_of, align_of};
+
+use super::arena_trait::{ArenaChunk, adjusted_allocation_count};
+use super::ArenaBox;
+
+/// A single 'chunk' of memory allocated on the heap, with a size of `N` bytes that is known at compile time.
+///
+/// Unlike [`InlineArena`](crate::inline_chunk::InlineArena), the memory is on the heap, so the chunk is small to move.
+/// As the size is a constant, the capacity checks can be specialised by the compiler.
+///
+/// With the `leak_check` feature, in debug builds, dropping the chunk while it has outstanding allocations panics,
+/// like [`SingleArena`](crate::single_chunk::SingleArena).
+pub struct ConstArena<const N: usize> {
+    start_pointer: *mut u8,
+    free_pointer: Cell<*mut u8>,
+    // allocations without a box, such as from `alloc_mut`, which aren't leaks
+    unowned_allocations: Cell<usize>,
+    pub allocations: Cell<usize>
+}
+
+impl<const N: usize> ConstArena<N> {
+    /// Create a new chunk of `N` bytes.
+    ///
+    /// `N` is checked at compile time to be greater than 0, and not greater than isize::MAX.
+    /// This is also checked when the chunk is created through [`ArenaChunk`].
+    ///
+    /// ```compile_fail
+    /// use arena::{ArenaChunk, const_chunk::ConstArena};
+    ///
+    /// let chunk = ConstArena::<0>::new();
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use arena::{ArenaChunk, const_chunk::ConstArena};
+    ///
+    /// let chunk = <ConstArena<0> as ArenaChunk>::new(1);
+    /// ```
+    pub fn new() -> Self {
+        // safety: the size is checked in `new_unchecked`, and is ignored
+        unsafe { Self::new_unchecked(N) }
+    }
+
+    /// Fail to compile if `N` is 0 or greater than isize::MAX.
+    const fn assert_valid_size() {
+        const { assert!(N != 0 && N <= isize::MAX as usize, "ConstArena size must be between 1 and isize::MAX") };
+    }
+}
+
+impl<const N: usize> ArenaChunk for ConstArena<N> {
+    /// Create a new chunk. `size` is ignored, as the size is always `N`, which is checked at compile time.
+    ///
+    /// # Safety
+    /// Always safe, as `N` is checked, but unsafe to match the trait.
+    unsafe fn new_unchecked(_size: usize) -> Self {
+        Self::assert_valid_size();
+        // safety: N has been checked
+        let allocation = Self::intialise_chunk(N);
+        Self { start_pointer: allocation, free_pointer: Cell::new(allocation), unowned_allocations: Cell::new(0), allocations: Cell::new(0) }
+    }
+
+    fn allocate<T>(&self, object: T) -> Option<ArenaBox<'_, T, Self>> {
+        let allocation_size = size_of::<T>();
+
+        // handle zst
+        if allocation_size == 0 {
+            // the box owns the zero sized value
+            std::mem::forget(object);
+            return Some(ArenaBox::new_zero_sized())
+        }
+
+        let offset = self.get_free_pointer_mut().align_offset(align_of::<T>());
+
+        // checks that there is enough free space to allocate this object
+        if allocation_size.checked_add(offset)? <= self.remaining_capacity() {
+            // safety: there is enough remaining capacity to store the object.
+            unsafe { Some(self.write_to_memory(object, allocation_size, offset)) }
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn get_start_pointer_mut(&self) -> *mut u8 {
+        self.start_pointer
+    }
+
+    #[inline]
+    fn get_free_pointer_mut(&self) -> *mut u8 {
+        self.free_pointer.get()
+    }
+
+    unsafe fn set_free_pointer(&self, ptr: *mut u8) {
+        self.free_pointer.set(ptr)
+    }
+
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        N - (self.free_pointer.get() as usize - self.start_pointer as usize)
+    }
+
+    fn adjust_allocation_count(&self, count: isize) {
+        self.allocations.set(adjusted_allocation_count(self.allocations.get(), count))
+    }
+
+    fn record_unowned_allocation(&self) {
+        self.unowned_allocations.set(self.unowned_allocations.get() + 1)
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Default for ConstArena<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Drop for ConstArena<N> {
+    fn drop(&mut self) {
+        // this isn't checked while panicking, as a second panic would abort
+        debug_assert!(
+            !cfg!(feature = "leak_check") || self.allocations.get() == self.unowned_allocations.get() || std::thread::panicking(),
+            "ConstArena dropped with {} outstanding allocations",
+            self.allocations.get() - self.unowned_allocations.get()
+        );
+
+        // drop means that there are no other references to the chunk, it can be safely deallocated.
+        unsafe {
+            self.deallocate_arena()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::single_chunk::SingleArena;
+
+    #[test]
+    fn matches_single_arena() {
+        let const_arena = ConstArena::<4096>::new();
+        let single_arena = SingleArena::new(4096).unwrap();
+        assert_eq!(const_arena.size(), single_arena.size());
+
+        let mut const_allocations = Vec::new();
+        let mut single_allocations = Vec::new();
+        for i in 0..500_u64 {
+            let (const_allocation, single_allocation) = (const_arena.allocate(i as u8), single_arena.allocate(i as u8));
+            assert_eq!(const_allocation.is_some(), single_allocation.is_some());
+            const_allocations.extend(const_allocation);
+            single_allocations.extend(single_allocation);
+
+            let (const_allocation, single_allocation) = (const_arena.allocate(i), single_arena.allocate(i));
+            assert_eq!(const_allocation.is_some(), single_allocation.is_some());
+            assert_eq!(const_arena.remaining_capacity(), single_arena.remaining_capacity());
+            drop((const_allocation, single_allocation));
+        }
+
+        assert_eq!(const_arena.remaining_capacity(), 0);
+        assert_eq!(const_arena.allocations.get(), single_arena.allocations.get());
+        assert!(const_allocations.iter().zip(&single_allocations).all(|(a, b)| **a == **b));
+    }
+}
@@ -0,0 +1,203 @@
+use std::cell::Cell;
+use std::mem::{size_of, align_of};
+use std::ptr::NonNull;
+
+use super::arena_trait::{ArenaChunk, adjusted_allocation_count};
+use super::ArenaBox;
+
+/// A single 'chunk' of allocated memory, which can allocate from both ends.
+///
+/// The front pointer moves from the start of the chunk towards the end, and the back pointer moves from the end towards the start.
+/// Allocations fail when the two pointers would cross.
+/// This is useful for separating long-lived and scratch allocations in the same chunk.
+///
+/// As an [`ArenaChunk`], the front pointer is used as the free pointer.
+pub struct DualArena {
+    size: usize,
+    start_pointer: *mut u8,
+    front_pointer: Cell<*mut u8>,
+    back_pointer: Cell<*mut u8>,
+    pub allocations: Cell<usize>
+}
+
+impl DualArena {
+    /// Allocate an object at the front of the chunk.
+    ///
+    /// Return None if there isn't enough space between the front and back pointers for the object.
+    pub fn allocate_front<T>(&self, object: T) -> Option<ArenaBox<'_, T, Self>> {
+        self.allocate(object)
+    }
+
+    /// Allocate an object at the back of the chunk.
+    ///
+    /// Return None if there isn't enough space between the front and back pointers for the object.
+    pub fn allocate_back<T>(&self, object: T) -> Option<ArenaBox<'_, T, Self>> {
+        let allocation_size = size_of::<T>();
+
+        // handle zst
+        if allocation_size == 0 {
+            // the box owns the zero sized value
+            std::mem::forget(object);
+            return Some(ArenaBox::new_zero_sized())
+        }
+
+        let back_pointer = self.back_pointer.get();
+        // round down to the alignment, which is a power of two
+        let address = (back_pointer as usize).checked_sub(allocation_size)? & !(align_of::<T>() - 1);
+        if address < self.front_pointer.get() as usize {
+            return None
+        }
+
+        unsafe {
+            // safety: the address is between the front pointer and the back pointer
+            let object_pointer = back_pointer.sub(back_pointer as usize - address);
+            self.back_pointer.set(object_pointer);
+
+            let object_pointer = object_pointer.cast::<T>();
+            std::ptr::write(object_pointer, object);
+            self.adjust_allocation_count(1);
+
+            // safety: object pointer is non-null
+            Some(ArenaBox::new(self, NonNull::new_unchecked(object_pointer)))
+        }
+    }
+
+    /// Return a pointer to the lowest address allocated at the back of the chunk.
+    #[inline]
+    pub fn get_back_pointer_mut(&self) -> *mut u8 {
+        self.back_pointer.get()
+    }
+}
+
+impl ArenaChunk for DualArena {
+    unsafe fn new_unchecked(size: usize) -> Self {
+        let allocation = Self::intialise_chunk(size);
+        Self {
+            size,
+            start_pointer: allocation,
+            front_pointer: Cell::new(allocation),
+            back_pointer: Cell::new(allocation.add(size)),
+            allocations: Cell::new(0)
+        }
+    }
+
+    fn allocate<T>(&self, object: T) -> Option<ArenaBox<'_, T, Self>> {
+        let allocation_size = size_of::<T>();
+
+        // handle zst
+        if allocation_size == 0 {
+            // the box owns the zero sized value
+            std::mem::forget(object);
+            return Some(ArenaBox::new_zero_sized())
+        }
+
+        let offset = self.get_free_pointer_mut().align_offset(align_of::<T>());
+
+        // checks that the front pointer won't pass the back pointer
+        if allocation_size.checked_add(offset)? <= self.remaining_capacity() {
+            // safety: there is enough remaining capacity to store the object.
+            unsafe { Some(self.write_to_memory(object, allocation_size, offset)) }
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn get_start_pointer_mut(&self) -> *mut u8 {
+        self.start_pointer
+    }
+
+    #[inline]
+    fn get_free_pointer_mut(&self) -> *mut u8 {
+        self.front_pointer.get()
+    }
+
+    unsafe fn set_free_pointer(&self, ptr: *mut u8) {
+        self.front_pointer.set(ptr)
+    }
+
+    /// Move the front pointer forward if it is between the front pointer and the back pointer inclusive.
+    fn try_set_free_pointer(&self, ptr: *mut u8) -> bool {
+        if ptr < self.front_pointer.get() || ptr > self.back_pointer.get() {
+            return false
+        }
+
+        self.front_pointer.set(ptr);
+        true
+    }
+
+    /// The number of bytes between the front and back pointers.
+    fn remaining_capacity(&self) -> usize {
+        self.back_pointer.get() as usize - self.front_pointer.get() as usize
+    }
+
+    fn adjust_allocation_count(&self, count: isize) {
+        self.allocations.set(adjusted_allocation_count(self.allocations.get(), count))
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Drop for DualArena {
+    fn drop(&mut self) {
+        // drop means that there are no other references to the chunk, it can be safely deallocated.
+        unsafe {
+            self.deallocate_arena()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleaved_allocations() {
+        let arena = DualArena::new(64).unwrap();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+
+        for i in 0..4_u64 {
+            front.push(arena.allocate_front(i).unwrap());
+            back.push(arena.allocate_back(i + 100).unwrap());
+        }
+
+        // the pointers have met
+        assert_eq!(arena.remaining_capacity(), 0);
+        assert_eq!(arena.get_free_pointer_mut(), arena.get_back_pointer_mut());
+        assert!(arena.allocate_front(0u8).is_none());
+        assert!(arena.allocate_back(0u8).is_none());
+
+        for i in 0..4 {
+            assert_eq!(*front[i], i as u64);
+            assert_eq!(*back[i], i as u64 + 100);
+        }
+        assert_eq!(arena.allocations.get(), 8);
+    }
+
+    #[test]
+    fn allocation_fails_when_pointers_cross() {
+        let arena = DualArena::new(10).unwrap();
+        let _front = arena.allocate_front([0u8; 4]).unwrap();
+        let _back = arena.allocate_back([0u8; 4]).unwrap();
+
+        assert!(arena.allocate_back([0u8; 3]).is_none());
+        assert!(arena.allocate_front([0u8; 3]).is_none());
+        assert!(arena.allocate_back([0u8; 2]).is_some());
+    }
+
+    #[test]
+    fn try_set_free_pointer_stops_at_back_pointer() {
+        let arena = DualArena::new(16).unwrap();
+        let _back = arena.allocate_back(0u64).unwrap();
+        let start = arena.get_start_pointer_mut();
+
+        assert!(!arena.try_set_free_pointer(start.wrapping_add(9)));
+        assert_eq!(arena.get_free_pointer_mut(), start);
+        assert!(arena.try_set_free_pointer(start.wrapping_add(8)));
+        assert_eq!(arena.remaining_capacity(), 0);
+    }
+}
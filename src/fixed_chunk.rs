@@ -0,0 +1,321 @@
+use std::alloc::{self, Layout};
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem::{size_of, align_of};
+use std::ptr::NonNull;
+
+use super::arena_trait::{ArenaChunk, PartialSliceGuard, adjusted_allocation_count, slice_size};
+use super::ArenaBox;
+
+/// A single 'chunk' of memory allocated on the heap, divided into slots for `T`s, which reuses the slots of dropped allocations.
+///
+/// When an allocation is dropped, its slot is pushed onto a freelist, which is a linked list threaded through the freed slots themselves.
+/// Allocations take the most recently freed slot before using new memory, so under churn the memory used is bounded by the
+/// peak number of live allocations.
+///
+/// Any object that fits in a slot can be allocated, and allocations that don't fit, such as slices of more than one `T`, return None.
+/// [`remaining_capacity`](ArenaChunk::remaining_capacity) is the memory that hasn't been used yet, so it doesn't include freed slots.
+/// The chunk can't be iterated, as its slots may hold other types than `T`, and each allocation is mutably borrowed by its `ArenaBox`.
+///
+/// With the `leak_check` feature, in debug builds, dropping the chunk while it has outstanding allocations panics,
+/// like [`SingleArena`](crate::single_chunk::SingleArena).
+pub struct FixedArena<T> {
+    size: usize,
+    start_pointer: *mut u8,
+    free_pointer: Cell<*mut u8>,
+    // the most recently freed slot, which holds a pointer to the next freed slot, or null if there are none
+    freelist: Cell<*mut u8>,
+    // allocations without a box, such as from `alloc_mut`, which aren't leaks
+    unowned_allocations: Cell<usize>,
+    pub allocations: Cell<usize>,
+    phantom: PhantomData<T>
+}
+
+impl<T> FixedArena<T> {
+    /// The alignment of each slot, which is aligned for a T and a freelist pointer.
+    const SLOT_ALIGN: usize = if align_of::<T>() > align_of::<*mut u8>() { align_of::<T>() } else { align_of::<*mut u8>() };
+
+    /// The size of each slot, which is large enough for a T or a freelist pointer, rounded up to the slot alignment.
+    pub const SLOT_SIZE: usize = {
+        let size = if size_of::<T>() > size_of::<*mut u8>() { size_of::<T>() } else { size_of::<*mut u8>() };
+        size.div_ceil(Self::SLOT_ALIGN) * Self::SLOT_ALIGN
+    };
+
+    /// Create a chunk with the capacity for `slots` Ts.
+    ///
+    /// Return None if `slots` is 0, or the size of the chunk is greater than isize::MAX.
+    pub fn with_capacity(slots: usize) -> Option<Self> {
+        Self::new(slots.checked_mul(Self::SLOT_SIZE)?)
+    }
+
+    /// The number of freed slots waiting to be reused.
+    pub fn free_slots(&self) -> usize {
+        self.freed_slots().count()
+    }
+
+    /// Iterate over the freelist, from the most recently freed slot.
+    fn freed_slots(&self) -> impl Iterator<Item = *mut u8> + '_ {
+        let mut slot = self.freelist.get();
+        std::iter::from_fn(move || {
+            if slot.is_null() {
+                return None
+            }
+            let freed = slot;
+            // safety: freed slots hold a pointer to the next freed slot
+            slot = unsafe { slot.cast::<*mut u8>().read() };
+            Some(freed)
+        })
+    }
+
+    fn layout(size: usize) -> Layout {
+        Layout::from_size_align(size, Self::SLOT_ALIGN).expect("FixedArena size is greater than isize::MAX")
+    }
+
+    /// Take the most recently freed slot, or a new slot if there are none. Return None if the chunk is full.
+    fn take_slot(&self) -> Option<*mut u8> {
+        let freed = self.freelist.get();
+        if !freed.is_null() {
+            // safety: freed slots hold a pointer to the next freed slot
+            self.freelist.set(unsafe { freed.cast::<*mut u8>().read() });
+            return Some(freed)
+        }
+
+        // the free pointer is only unaligned if it was set through the trait methods
+        let free_pointer = self.free_pointer.get();
+        let offset = free_pointer.align_offset(Self::SLOT_ALIGN);
+        if Self::SLOT_SIZE.checked_add(offset)? > self.remaining_capacity() {
+            return None
+        }
+
+        // safety: there is enough remaining capacity for the offset and the slot
+        let slot = unsafe { free_pointer.add(offset) };
+        self.free_pointer.set(unsafe { slot.add(Self::SLOT_SIZE) });
+        Some(slot)
+    }
+}
+
+impl<T> ArenaChunk for FixedArena<T> {
+    /// Create a new chunk of `size` bytes, aligned for the slots.
+    ///
+    /// # Safety
+    /// UB if size is 0.
+    ///
+    /// # Panics
+    /// If the size rounded up to the slot alignment is greater than isize::MAX.
+    unsafe fn new_unchecked(size: usize) -> Self {
+        let layout = Self::layout(size);
+        let allocation = match Self::try_intialise_chunk_aligned(size, Self::SLOT_ALIGN) {
+            Some(allocation) => allocation,
+            None => alloc::handle_alloc_error(layout)
+        };
+
+        Self {
+            size,
+            start_pointer: allocation,
+            free_pointer: Cell::new(allocation),
+            freelist: Cell::new(std::ptr::null_mut()),
+            unowned_allocations: Cell::new(0),
+            allocations: Cell::new(0),
+            phantom: PhantomData
+        }
+    }
+
+    /// Allocate an object in a slot. Return None if the object doesn't fit in a slot, or there are no free slots.
+    fn allocate<U>(&self, object: U) -> Option<ArenaBox<'_, U, Self>> {
+        // handle zst
+        if size_of::<U>() == 0 {
+            // the box owns the zero sized value
+            std::mem::forget(object);
+            return Some(ArenaBox::new_zero_sized())
+        }
+
+        let object_pointer = self.reserve_bytes(size_of::<U>(), align_of::<U>())?.cast::<U>();
+
+        unsafe {
+            std::ptr::write(object_pointer, object);
+            self.adjust_allocation_count(1);
+
+            // safety: object pointer is non-null
+            Some(ArenaBox::new(self, NonNull::new_unchecked(object_pointer)))
+        }
+    }
+
+    /// Allocate an object in a slot.
+    ///
+    /// # Safety
+    /// The object must not be a ZST, and must fit in a free slot.
+    unsafe fn allocate_unchecked<U>(&self, object: U) -> ArenaBox<'_, U, Self> {
+        // objects are never written at the free pointer, so that slots don't overlap
+        self.allocate(object).unwrap_unchecked()
+    }
+
+    /// Reserve a slot, if `size` and `align` fit in a slot.
+    fn reserve_bytes(&self, size: usize, align: usize) -> Option<*mut u8> {
+        if size > Self::SLOT_SIZE || align > Self::SLOT_ALIGN {
+            return None
+        }
+        self.take_slot()
+    }
+
+    /// Grow an allocated object into a slice of `new_len` elements in its slot, where the object is the first element,
+    /// and the other elements are `U::default()`.
+    ///
+    /// The original box is returned if `new_len` is 0, or the slice doesn't fit in a slot, as a freed slice
+    /// only returns one slot to the freelist.
+    fn try_reallocate<'a, U: Default>(&'a self, old: ArenaBox<'a, U, Self>, new_len: usize) -> Result<ArenaBox<'a, [U], Self>, ArenaBox<'a, U, Self>> {
+        if new_len == 0 {
+            return Err(old)
+        }
+
+        // handle zst
+        if size_of::<U>() == 0 {
+            std::mem::forget(ArenaBox::into_inner(old));
+            for _ in 1..new_len {
+                std::mem::forget(U::default());
+            }
+            // safety: the zero sized Us have been forgotten, so the box owns them
+            return Ok(unsafe { ArenaBox::new_zero_sized_slice(new_len) })
+        }
+
+        if slice_size::<U>(new_len).is_none_or(|size| size > Self::SLOT_SIZE) {
+            return Err(old)
+        }
+
+        // the allocation count of the old box is kept for the slice, in the chunk that owns the slot
+        let (pointer, arena) = ArenaBox::into_raw(old);
+        let arena = arena.unwrap_or(self);
+        let slice_pointer = pointer.as_ptr();
+
+        unsafe {
+            // drops the initialised elements if U::default() panics
+            let mut guard = PartialSliceGuard { arena, start: slice_pointer, initialised: 1 };
+            while guard.initialised < new_len {
+                std::ptr::write(slice_pointer.add(guard.initialised), U::default());
+                guard.initialised += 1;
+            }
+            std::mem::forget(guard);
+
+            // safety: the slice fits in the object's slot, and all the elements are initialised
+            let slice = NonNull::slice_from_raw_parts(pointer, new_len);
+            Ok(ArenaBox::new(arena, slice))
+        }
+    }
+
+    #[inline]
+    fn get_start_pointer_mut(&self) -> *mut u8 {
+        self.start_pointer
+    }
+
+    #[inline]
+    fn get_free_pointer_mut(&self) -> *mut u8 {
+        self.free_pointer.get()
+    }
+
+    unsafe fn set_free_pointer(&self, ptr: *mut u8) {
+        self.free_pointer.set(ptr)
+    }
+
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        self.size - (self.free_pointer.get() as usize - self.start_pointer as usize)
+    }
+
+    fn adjust_allocation_count(&self, count: isize) {
+        self.allocations.set(adjusted_allocation_count(self.allocations.get(), count))
+    }
+
+    fn record_unowned_allocation(&self) {
+        self.unowned_allocations.set(self.unowned_allocations.get() + 1)
+    }
+
+    /// Push the allocation's slot onto the freelist, to be reused by the next allocation.
+    unsafe fn free_allocation(&self, ptr: *mut u8) {
+        self.adjust_allocation_count(-1);
+        // safety: the slot is aligned and large enough for a pointer, and is unused
+        ptr.cast::<*mut u8>().write(self.freelist.get());
+        self.freelist.set(ptr);
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn alignment(&self) -> usize {
+        Self::SLOT_ALIGN
+    }
+}
+
+impl<T> Drop for FixedArena<T> {
+    fn drop(&mut self) {
+        // this isn't checked while panicking, as a second panic would abort
+        debug_assert!(
+            !cfg!(feature = "leak_check") || self.allocations.get() == self.unowned_allocations.get() || std::thread::panicking(),
+            "FixedArena dropped with {} outstanding allocations",
+            self.allocations.get() - self.unowned_allocations.get()
+        );
+
+        // drop means that there are no other references to the chunk, it can be safely deallocated.
+        unsafe {
+            self.deallocate_arena()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn churn_is_bounded_by_live_count() {
+        let arena = FixedArena::<String>::with_capacity(64).unwrap();
+
+        for round in 0..100 {
+            let live: Vec<_> = (0..5).map(|i| arena.allocate(format!("{round} {i}")).unwrap()).collect();
+            assert_eq!(*live[4], format!("{round} 4"));
+            // the chunk never uses more than the peak of 5 live slots
+            assert_eq!(arena.used_capacity(), 5 * FixedArena::<String>::SLOT_SIZE);
+        }
+        assert_eq!(arena.free_slots(), 5);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn freed_slot_is_reused() {
+        let arena = FixedArena::<u64>::with_capacity(2).unwrap();
+        let first = arena.allocate(1u64).unwrap();
+        let _second = arena.allocate(2u64).unwrap();
+        assert!(arena.allocate(3u64).is_none());
+
+        let first_address = &*first as *const u64;
+        drop(first);
+        let third = arena.allocate(3u64).unwrap();
+        assert_eq!(&*third as *const u64, first_address);
+        assert_eq!(*third, 3);
+
+        // objects which don't fit in a slot can't be allocated
+        drop(third);
+        assert!(arena.allocate([0u64; 2]).is_none());
+        assert!(arena.allocate(5u32).is_some());
+    }
+
+    #[test]
+    fn reallocate_within_slot() {
+        let arena = FixedArena::<u64>::with_capacity(4).unwrap();
+        let allocation = arena.allocate(1u64).unwrap();
+
+        // a slice larger than a slot would leak slots when freed
+        let allocation = arena.try_reallocate(allocation, 3).err().unwrap();
+        assert_eq!(arena.used_capacity(), FixedArena::<u64>::SLOT_SIZE);
+        drop(allocation);
+        assert_eq!(arena.free_slots(), 1);
+
+        let small = arena.allocate(7u32).unwrap();
+        let slice = arena.try_reallocate(small, 2).ok().unwrap();
+        assert_eq!(*slice, [7, 0]);
+        assert_eq!(arena.used_capacity(), FixedArena::<u64>::SLOT_SIZE);
+        drop(slice);
+        assert_eq!(arena.free_slots(), 1);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+}
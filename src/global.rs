@@ -0,0 +1,75 @@
+use std::alloc::{GlobalAlloc, Layout};
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// An arena of `N` bytes which can be used as the global allocator, so that all allocations come from a preallocated region.
+/// 
+/// The memory is stored inline, so the arena can be constructed in a `static`, and allocates by bumping an atomic offset.
+/// Deallocation does nothing, and allocations return null (an allocation error) once the arena is full.
+/// 
+/// ```no_run
+/// use arena::ArenaGlobal;
+/// 
+/// #[global_allocator]
+/// static GLOBAL: ArenaGlobal<{ 1024 * 1024 }> = ArenaGlobal::new();
+/// ```
+pub struct ArenaGlobal<const N: usize> {
+    buffer: UnsafeCell<[MaybeUninit<u8>; N]>,
+    offset: AtomicUsize
+}
+
+// safety: the buffer is only accessed through the regions returned by alloc,
+// which are disjoint as the offset is bumped atomically
+unsafe impl<const N: usize> Sync for ArenaGlobal<N> {}
+
+impl<const N: usize> ArenaGlobal<N> {
+    pub const fn new() -> Self {
+        Self { buffer: UnsafeCell::new([MaybeUninit::uninit(); N]), offset: AtomicUsize::new(0) }
+    }
+
+    /// The number of bytes that have been allocated, including alignment padding.
+    pub fn used(&self) -> usize {
+        self.offset.load(Ordering::Relaxed)
+    }
+
+    /// The remaining capacity of the arena in bytes.
+    pub fn remaining_capacity(&self) -> usize {
+        N - self.used()
+    }
+
+    #[inline]
+    fn start_pointer(&self) -> *mut u8 {
+        self.buffer.get().cast::<u8>()
+    }
+}
+
+impl<const N: usize> Default for ArenaGlobal<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const N: usize> GlobalAlloc for ArenaGlobal<N> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let start_pointer = self.start_pointer();
+        let mut offset = self.offset.load(Ordering::Relaxed);
+
+        loop {
+            let padding = start_pointer.add(offset).align_offset(layout.align());
+            let new_offset = match offset.checked_add(padding).and_then(|offset| offset.checked_add(layout.size())) {
+                Some(new_offset) if new_offset <= N => new_offset,
+                _ => return std::ptr::null_mut()
+            };
+
+            match self.offset.compare_exchange_weak(offset, new_offset, Ordering::Relaxed, Ordering::Relaxed) {
+                // safety: the region is inside the buffer, and no other allocation can overlap it
+                Ok(_) => return start_pointer.add(offset + padding),
+                Err(current) => offset = current
+            }
+        }
+    }
+
+    /// Memory in the arena is never reused, so deallocating does nothing.
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+}
@@ -0,0 +1,136 @@
+use std::cell::{Cell, UnsafeCell};
+use std::mem::{size_of, align_of, MaybeUninit};
+
+use super::arena_trait::{ArenaChunk, adjusted_allocation_count};
+use super::ArenaBox;
+
+/// A 'chunk' of memory stored inline in the struct, so it doesn't allocate on the heap.
+///
+/// Useful for short-lived allocations that fit in a few hundred bytes, where the chunk can be kept on the stack.
+/// The chunk always has a size of `N` bytes.
+///
+/// The free pointer is stored as an offset from the start of the buffer, so the chunk can be moved while it has no allocations.
+pub struct InlineArena<const N: usize> {
+    buffer: UnsafeCell<[MaybeUninit<u8>; N]>,
+    free_offset: Cell<usize>,
+    pub allocations: Cell<usize>
+}
+
+impl<const N: usize> ArenaChunk for InlineArena<N> {
+    /// Create a new inline chunk of `N` bytes.
+    ///
+    /// # Panics
+    /// If `size` isn't `N`, as the size of the buffer is fixed.
+    unsafe fn new_unchecked(size: usize) -> Self {
+        assert_eq!(size, N, "InlineArena size must be N");
+        Self { buffer: UnsafeCell::new([MaybeUninit::uninit(); N]), free_offset: Cell::new(0), allocations: Cell::new(0) }
+    }
+
+    /// Create a new inline chunk, or return None if `size` isn't `N`, or is 0.
+    fn new(size: usize) -> Option<Self> {
+        if size == 0 || size != N {
+            return None
+        }
+        // safety: the size is N
+        Some(unsafe { Self::new_unchecked(size) })
+    }
+
+    fn allocate<T>(&self, object: T) -> Option<ArenaBox<'_, T, Self>> {
+        let allocation_size = size_of::<T>();
+
+        // handle zst
+        if allocation_size == 0 {
+            // the box owns the zero sized value
+            std::mem::forget(object);
+            return Some(ArenaBox::new_zero_sized())
+        }
+
+        let offset = self.get_free_pointer_mut().align_offset(align_of::<T>());
+
+        // checks that there is enough free space to allocate this object
+        if allocation_size.checked_add(offset)? <= self.remaining_capacity() {
+            // safety: there is enough remaining capacity to store the object.
+            unsafe { Some(self.write_to_memory(object, allocation_size, offset)) }
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn get_start_pointer_mut(&self) -> *mut u8 {
+        self.buffer.get().cast::<u8>()
+    }
+
+    #[inline]
+    fn get_free_pointer_mut(&self) -> *mut u8 {
+        // safety: the free offset is never greater than N
+        unsafe { self.get_start_pointer_mut().add(self.free_offset.get()) }
+    }
+
+    unsafe fn set_free_pointer(&self, ptr: *mut u8) {
+        self.free_offset.set(ptr as usize - self.get_start_pointer_mut() as usize)
+    }
+
+    fn remaining_capacity(&self) -> usize {
+        N - self.free_offset.get()
+    }
+
+    fn adjust_allocation_count(&self, count: isize) {
+        self.allocations.set(adjusted_allocation_count(self.allocations.get(), count))
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        N
+    }
+
+    /// The buffer isn't allocated on the heap, so there is nothing to deallocate.
+    unsafe fn deallocate_arena(&mut self) {}
+}
+
+impl<const N: usize> Default for InlineArena<N> {
+    fn default() -> Self {
+        // safety: the size is N
+        unsafe { Self::new_unchecked(N) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_allocations() {
+        let arena = InlineArena::<256>::default();
+        let arena_start = &arena as *const InlineArena<256> as usize;
+        let arena_end = arena_start + size_of::<InlineArena<256>>();
+
+        let integer = arena.allocate(5u64).unwrap();
+        let byte = arena.allocate(1u8).unwrap();
+        let array = arena.allocate([3u16; 4]).unwrap();
+        assert_eq!((*integer, *byte, *array), (5, 1, [3; 4]));
+        assert_eq!(arena.allocations.get(), 3);
+
+        // the allocations are stored in the struct, not on the heap
+        for pointer in [unsafe { ArenaBox::const_ptr(&integer) } as usize, unsafe { ArenaBox::const_ptr(&array) } as usize] {
+            assert!(pointer >= arena_start && pointer < arena_end);
+        }
+
+        assert!(arena.allocate([0u8; 256]).is_none());
+    }
+
+    #[test]
+    fn full_buffer_and_size() {
+        assert!(<InlineArena<256> as ArenaChunk>::new(4096).is_none());
+        assert!(<InlineArena<256> as ArenaChunk>::new(0).is_none());
+
+        let arena = <InlineArena<16> as ArenaChunk>::new(16).unwrap();
+        let first = arena.allocate([1u8; 16]).unwrap();
+        assert_eq!(arena.remaining_capacity(), 0);
+        assert!(arena.allocate(2u8).is_none());
+        // dropping an allocation doesn't free its memory
+        drop(first);
+        assert!(arena.allocate(2u8).is_none());
+        assert_eq!(arena.allocations.get(), 0);
+    }
+}
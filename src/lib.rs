@@ -1,12 +1,39 @@
+#![cfg_attr(feature = "nightly", feature(coerce_unsized, unsize))]
+
 pub mod single_chunk;
+pub mod inline_chunk;
+pub mod dual_chunk;
+pub mod const_chunk;
+pub mod fixed_chunk;
+pub mod misc;
 
 mod chunk_linked_list;
 
 mod arena_allocator;
 pub use arena_allocator::*;
 
+mod arena_builder;
+pub use arena_builder::*;
+
 mod arena_box;
 pub use arena_box::*;
 
 mod arena_trait;
-pub use arena_trait::*;
\ No newline at end of file
+pub use arena_trait::*;
+
+mod arena_slice;
+pub use arena_slice::*;
+
+mod arena_vec;
+pub use arena_vec::*;
+
+mod arena_interner;
+pub use arena_interner::*;
+
+mod global;
+pub use global::*;
+
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "serde")]
+pub use serde_impls::*;
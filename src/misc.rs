@@ -0,0 +1,33 @@
+/// Reinterpret `count` Ts of memory starting at `start` as a slice, such as to inspect typed arena contents.
+/// 
+/// # Panics
+/// If `count * size_of::<T>()` overflows or is greater than isize::MAX.
+/// In debug builds, if `start` isn't aligned for T.
+/// 
+/// # Safety
+/// The memory must contain `count` valid, initialised Ts, and must not be mutated or freed while the slice is used.
+/// The lifetime `'a` is unbounded, so the caller must make sure that the slice doesn't outlive the arena.
+pub unsafe fn read_segment_as<'a, T>(start: *const u8, count: usize) -> &'a [T] {
+    let byte_size = std::mem::size_of::<T>().checked_mul(count);
+    assert!(byte_size.is_some_and(|size| size <= isize::MAX as usize), "Segment size overflows");
+    debug_assert!(start.cast::<T>().is_aligned(), "Segment isn't aligned for the type");
+
+    std::slice::from_raw_parts(start.cast::<T>(), count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArenaChunk;
+    use crate::single_chunk::SingleArena;
+
+    #[test]
+    fn read_u32_segment() {
+        let arena = SingleArena::new(100).unwrap();
+        let allocations: Vec<_> = (0..10_u32).map(|i| arena.allocate(i * 3).unwrap()).collect();
+
+        let values = unsafe { read_segment_as::<u32>(arena.get_start_pointer_mut(), 10) };
+        assert_eq!(values, (0..10).map(|i| i * 3).collect::<Vec<u32>>());
+        drop(allocations);
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de::Error};
+
+use super::{ArenaBox, ArenaChunk};
+
+impl<'a, T: ?Sized + Serialize, A: ArenaChunk> Serialize for ArenaBox<'a, T, A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+/// Deserialize a T and allocate it in an arena chunk.
+/// 
+/// `ArenaBox` can't implement `Deserialize`, as the arena can't be passed through the trait.
+/// 
+/// Returns an error if the chunk doesn't have the capacity for the object.
+pub fn deserialize_into_arena<'a, 'de, T, A, D>(arena: &'a A, deserializer: D) -> Result<ArenaBox<'a, T, A>, D::Error>
+where
+    T: Deserialize<'de>,
+    A: ArenaChunk,
+    D: Deserializer<'de>
+{
+    let object = T::deserialize(deserializer)?;
+    arena.allocate(object).ok_or_else(|| D::Error::custom("arena chunk doesn't have the capacity for the object"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::single_chunk::SingleArena;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Foo {
+        integer: i64,
+        string: String,
+        array: [u8; 4]
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let arena = SingleArena::new(100).unwrap();
+        let allocation = arena.allocate(Foo { integer: 4, string: String::from("test string"), array: [1, 2, 3, 4] }).unwrap();
+        let json = serde_json::to_string(&allocation).unwrap();
+
+        let new_arena = SingleArena::new(100).unwrap();
+        let deserialized: ArenaBox<Foo, _> = deserialize_into_arena(&new_arena, &mut serde_json::Deserializer::from_str(&json)).unwrap();
+        assert_eq!(*deserialized, *allocation);
+        assert_eq!(new_arena.allocations.get(), 1);
+
+        let zst = arena.allocate(()).unwrap();
+        let json = serde_json::to_string(&zst).unwrap();
+        let _: ArenaBox<(), _> = deserialize_into_arena(&new_arena, &mut serde_json::Deserializer::from_str(&json)).unwrap();
+
+        // not enough capacity
+        let small_arena = SingleArena::new(8).unwrap();
+        let json = serde_json::to_string(&allocation).unwrap();
+        assert!(deserialize_into_arena::<Foo, _, _>(&small_arena, &mut serde_json::Deserializer::from_str(&json)).is_err());
+    }
+}
@@ -1,42 +1,624 @@
 use std::cell::Cell;
-use std::mem::{size_of, align_of};
+use std::mem::{size_of, align_of, MaybeUninit};
+use std::ptr::NonNull;
 
-use super::arena_trait::ArenaChunk;
+use super::arena_trait::{ArenaChunk, adjusted_allocation_count};
 use super::ArenaBox;
 
 /// A single 'chunk' or 'block' of allocated memory.
 /// 
 /// The chunk has a constant size, and only allocates memory once, when creating the chunk.
 /// This means that allocations can fail if there is no capacity remaining.
+/// 
+/// With the `leak_check` feature, in debug builds, dropping the chunk while it has outstanding allocations
+/// (such as leaked `ArenaBox`es) panics, to find leaks. Leaking is safe, so this is off by default.
+/// 
+/// # Thread safety
+/// The free pointer and allocation count are stored in `Cell`s, so `SingleArena` is neither `Send` nor `Sync`.
+/// 
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<arena::single_chunk::SingleArena>();
+/// ```
+/// 
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<arena::single_chunk::SingleArena>();
+/// ```
 pub struct SingleArena {
     size: usize,
     start_pointer: *mut u8,
     free_pointer: Cell<*mut u8>,
+    direction: BumpDirection,
+    zeroizing: bool,
+    poisoning: bool,
+    count_zsts: bool,
+    failed_allocations: Cell<usize>,
+    // allocations without a box, such as from `alloc_mut`, which aren't leaks
+    unowned_allocations: Cell<usize>,
+    alignment_waste: Cell<usize>,
+    // the alignment that the memory was allocated with
+    align: usize,
     pub allocations: Cell<usize>
 }
 
+/// A position in a [`SingleArena`], which it can be rewound to.
+#[derive(Clone, Copy, Debug)]
+pub struct Marker {
+    free_pointer: *mut u8,
+    allocations: usize,
+    unowned_allocations: usize
+}
+
+/// The byte that free memory in a poisoning [`SingleArena`] is filled with.
+pub const POISON_BYTE: u8 = 0xDD;
+
+/// A function which runs the destructor of an object at a pointer, without deallocating it.
+pub type DropGlue = unsafe fn(*mut u8);
+
+/// The direction that the free pointer of a [`SingleArena`] moves in when allocating.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BumpDirection {
+    /// From the start of the chunk towards the end.
+    Upward,
+    /// From the end of the chunk towards the start.
+    Downward
+}
+
+impl SingleArena {
+    /// Create a new chunk which allocates from the end of its memory towards the start, checking that the size is valid.
+    /// 
+    /// `allocate_unchecked` and `write_to_memory` assume that the free pointer moves upward, so must not be used with this chunk.
+    pub fn new_downward(size: usize) -> Option<Self> {
+        let mut arena = Self::new(size)?;
+        // safety: the end of the memory is one byte past the allocation
+        arena.free_pointer.set(unsafe { arena.start_pointer.add(size) });
+        arena.direction = BumpDirection::Downward;
+        Some(arena)
+    }
+
+    #[inline]
+    pub fn direction(&self) -> BumpDirection {
+        self.direction
+    }
+
+    /// Create a new chunk which overwrites its used region with zeros when it is reset or dropped,
+    /// so secrets such as keys don't remain in freed memory. Checks that the size is valid.
+    /// 
+    /// Memory freed by [`rewind`](SingleArena::rewind) isn't zeroed.
+    pub fn new_zeroizing(size: usize) -> Option<Self> {
+        let mut arena = Self::new(size)?;
+        arena.zeroizing = true;
+        Some(arena)
+    }
+
+    #[inline]
+    pub fn is_zeroizing(&self) -> bool {
+        self.zeroizing
+    }
+
+    /// Choose whether the chunk overwrites its used region with zeros when it is reset or dropped, as with [`new_zeroizing`](SingleArena::new_zeroizing).
+    pub fn set_zeroizing(&mut self, zeroizing: bool) {
+        self.zeroizing = zeroizing;
+    }
+
+    /// Whether free memory in the chunk is filled with [`POISON_BYTE`], to make use of freed memory visible.
+    /// 
+    /// Chunks are poisoning by default when the `poison` feature is enabled.
+    #[inline]
+    pub fn is_poisoning(&self) -> bool {
+        self.poisoning
+    }
+
+    /// Choose whether free memory in the chunk is filled with [`POISON_BYTE`] when the chunk is created, rewound or reset.
+    /// 
+    /// Enabling poisoning fills the current free memory.
+    pub fn set_poisoning(&mut self, poisoning: bool) {
+        if poisoning && !self.poisoning {
+            let (start, len) = match self.direction {
+                BumpDirection::Upward => (self.free_pointer.get(), self.remaining_capacity()),
+                BumpDirection::Downward => (self.start_pointer, self.remaining_capacity())
+            };
+            // safety: the free memory is inside the chunk, and isn't referenced
+            unsafe { std::ptr::write_bytes(start, POISON_BYTE, len) };
+        }
+        self.poisoning = poisoning;
+    }
+
+    /// Choose whether zero sized types allocated with `allocate` are counted as allocations, such as to detect leaked ZSTs.
+    /// 
+    /// They aren't counted by default. Counted ZST boxes belong to the chunk, so [`ArenaBox::arena`] returns it.
+    pub fn set_count_zsts(&mut self, count_zsts: bool) {
+        self.count_zsts = count_zsts;
+    }
+
+    /// The number of times that allocating or reserving memory in the chunk failed, such as to detect an undersized chunk.
+    pub fn failed_allocation_count(&self) -> usize {
+        self.failed_allocations.get()
+    }
+
+    /// The total number of bytes skipped to align allocations, over the lifetime of the chunk.
+    /// 
+    /// A high waste means that the allocations would be packed tighter if they were made in order of decreasing alignment.
+    pub fn alignment_waste(&self) -> usize {
+        self.alignment_waste.get()
+    }
+
+    /// Reserve memory as in `reserve_bytes`, without counting a failure.
+    fn try_reserve_bytes(&self, size: usize, align: usize) -> Option<*mut u8> {
+        let free_pointer = self.free_pointer.get();
+
+        match self.direction {
+            BumpDirection::Upward => {
+                let offset = free_pointer.align_offset(align);
+                if size.checked_add(offset)? > self.remaining_capacity() {
+                    return None
+                }
+
+                self.record_alignment_padding(offset);
+
+                // safety: there is enough remaining capacity for the offset and the memory
+                let ptr = unsafe { free_pointer.add(offset) };
+                self.free_pointer.set(unsafe { ptr.add(size) });
+                Some(ptr)
+            },
+            BumpDirection::Downward => {
+                // round down to the alignment, which is a power of two
+                let address = (free_pointer as usize).checked_sub(size)? & !(align - 1);
+                if address < self.start_pointer as usize {
+                    return None
+                }
+
+                // the padding is between the end of the memory and the free pointer
+                self.record_alignment_padding(free_pointer as usize - size - address);
+
+                // safety: the address is between the start pointer and the free pointer
+                let ptr = unsafe { free_pointer.sub(free_pointer as usize - address) };
+                self.free_pointer.set(ptr);
+                Some(ptr)
+            }
+        }
+    }
+
+    /// Create a new chunk, checking that the size is valid. Return None if the size is invalid,
+    /// or if allocating the memory fails, instead of aborting like `new`.
+    pub fn try_new(size: usize) -> Option<Self> {
+        if size == 0 || size > isize::MAX as usize {
+            return None
+        }
+        // safety: the size has been checked
+        unsafe { Some(Self::from_allocation(Self::try_intialise_chunk(size)?, size, 1)) }
+    }
+
+    /// Create a new chunk whose memory is aligned to `align`, such as to a page, so that allocations aligned up to `align`
+    /// don't need padding at the start of the chunk.
+    /// 
+    /// Return None if the size is 0, `align` isn't a power of two, the size rounded up to `align` is greater than isize::MAX,
+    /// or allocating the memory fails.
+    pub fn new_aligned(size: usize, align: usize) -> Option<Self> {
+        if size == 0 || std::alloc::Layout::from_size_align(size, align).is_err() {
+            return None
+        }
+        // safety: the size and alignment have been checked
+        unsafe { Some(Self::from_allocation(Self::try_intialise_chunk_aligned(size, align)?, size, align)) }
+    }
+
+    /// # Safety
+    /// The allocation must be from `intialise_chunk`, or `try_intialise_chunk_aligned` with `align`, with the same size.
+    unsafe fn from_allocation(allocation: *mut u8, size: usize, align: usize) -> Self {
+        let poisoning = cfg!(feature = "poison");
+        if poisoning {
+            std::ptr::write_bytes(allocation, POISON_BYTE, size);
+        }
+
+        Self {
+            size,
+            start_pointer: allocation,
+            free_pointer: Cell::new(allocation),
+            direction: BumpDirection::Upward,
+            zeroizing: false,
+            poisoning,
+            count_zsts: false,
+            failed_allocations: Cell::new(0),
+            unowned_allocations: Cell::new(0),
+            alignment_waste: Cell::new(0),
+            align,
+            allocations: Cell::new(0)
+        }
+    }
+
+    /// Return a pointer to the start of the used region, and its length in bytes.
+    fn used_region(&self) -> (*mut u8, usize) {
+        let start = match self.direction {
+            BumpDirection::Upward => self.start_pointer,
+            BumpDirection::Downward => self.free_pointer.get()
+        };
+        (start, self.used_capacity())
+    }
+
+    /// Overwrite the used region with zeros, if the chunk is zeroizing.
+    fn zeroize_used_region(&mut self) {
+        if !self.zeroizing {
+            return
+        }
+
+        let (start, len) = self.used_region();
+        // safety: there are no references to the chunk's memory
+        unsafe { zeroize(start, len) };
+    }
+
+    /// Return the used region of the chunk, such as to write a snapshot of the chunk's memory.
+    /// 
+    /// The bytes are `MaybeUninit`, as alignment padding and padding inside allocated types may be uninitialised,
+    /// so a byte must only be read with `assume_init` if it was written by an allocation.
+    /// 
+    /// # Aliasing
+    /// The slice is a view of memory owned by the chunk's allocations, so it must not be held while an allocation
+    /// is modified through its `ArenaBox`, or through a reference from [`alloc_mut`](ArenaChunk::alloc_mut).
+    /// Copy the bytes out, such as into a `Vec`, before modifying any allocation.
+    pub fn used_slice(&self) -> &[MaybeUninit<u8>] {
+        let (start, len) = self.used_region();
+        // safety: the used region is in the chunk, and MaybeUninit doesn't need to be initialised
+        unsafe { std::slice::from_raw_parts(start.cast_const().cast::<MaybeUninit<u8>>(), len) }
+    }
+
+    /// Create a chunk of the same size and settings, with a copy of the used region at the same offsets, such as to checkpoint the chunk.
+    /// 
+    /// **The copy is only meaningful if every allocation is plain old data**, such as integers or arrays of them,
+    /// as the bytes are copied without cloning. Copying a `String`, `Box` or any other type which owns memory
+    /// or has a destructor gives two owners of the same resource.
+    /// 
+    /// The copy has no allocations, as no `ArenaBox`es own its memory, so its objects can only be reached through pointers,
+    /// at the same offsets from the start pointer as in this chunk.
+    /// The bytes are copied as `MaybeUninit`, like [`used_slice`](SingleArena::used_slice), so padding is copied as it is.
+    /// 
+    /// Aborts if allocating the memory fails, like `new`.
+    pub fn clone_bytes(&self) -> SingleArena {
+        let layout = std::alloc::Layout::from_size_align(self.size, self.align).expect("SingleArena size is greater than isize::MAX");
+        // safety: the size and alignment are the same as this chunk's, which were checked
+        let mut clone = unsafe {
+            match Self::try_intialise_chunk_aligned(self.size, self.align) {
+                Some(allocation) => Self::from_allocation(allocation, self.size, self.align),
+                None => std::alloc::handle_alloc_error(layout)
+            }
+        };
+        clone.direction = self.direction;
+        clone.zeroizing = self.zeroizing;
+        clone.poisoning = self.poisoning;
+        clone.count_zsts = self.count_zsts;
+
+        let (start, len) = self.used_region();
+        let offset = start as usize - self.start_pointer as usize;
+        let free_offset = self.free_pointer.get() as usize - self.start_pointer as usize;
+        // safety: the offsets are in both chunks, which are the same size and don't overlap.
+        // the bytes are copied as uninitialised memory, so padding doesn't need to be initialised
+        unsafe {
+            std::ptr::copy_nonoverlapping(start, clone.start_pointer.add(offset), len);
+            clone.free_pointer.set(clone.start_pointer.add(free_offset));
+        }
+        clone
+    }
+
+    /// Return whether the chunk has at least `bytes` bytes of remaining capacity, such as to check once before a burst of allocations.
+    /// 
+    /// Nothing is reserved, but until the next allocation, `bytes` bytes of allocations, including their alignment padding,
+    /// can be made with [`allocate_unchecked`](ArenaChunk::allocate_unchecked) without checking the capacity of each.
+    /// 
+    /// This only holds for upward chunks, as `allocate_unchecked` bumps the free pointer upward,
+    /// so this always returns false for chunks made with [`new_downward`](SingleArena::new_downward).
+    pub fn reserve(&self, bytes: usize) -> bool {
+        self.direction == BumpDirection::Upward && bytes <= self.remaining_capacity()
+    }
+
+    /// Write to every page of the remaining capacity, so that the OS commits the pages before the chunk is used,
+    /// such as before a latency sensitive loop of allocations which would otherwise page fault on the first write to each page.
+    /// 
+    /// This is best-effort, as the OS may still swap the pages out later. The used region isn't written to,
+    /// and a poisoning chunk writes [`POISON_BYTE`], so the free memory stays poisoned.
+    pub fn prefault(&self) {
+        let remaining = self.remaining_capacity();
+        let start = match self.direction {
+            BumpDirection::Upward => self.free_pointer.get(),
+            BumpDirection::Downward => self.start_pointer
+        };
+
+        let byte = if self.poisoning { POISON_BYTE } else { 0 };
+        for offset in (0..remaining).step_by(PREFAULT_PAGE_SIZE) {
+            // safety: the offset is in the remaining capacity, which no allocation uses.
+            // the write is volatile so it isn't elided as a dead store
+            unsafe { std::ptr::write_volatile(start.add(offset), byte) };
+        }
+    }
+
+    /// Record the current free pointer and allocation count, to be rewound to later.
+    pub fn mark(&self) -> Marker {
+        Marker {
+            free_pointer: self.free_pointer.get(),
+            allocations: self.allocations.get(),
+            unowned_allocations: self.unowned_allocations.get()
+        }
+    }
+
+    /// Reset the free pointer and allocation count to a marker, freeing everything allocated since the marker was made.
+    /// 
+    /// The destructors of the freed objects are NOT run. A poisoning chunk fills the freed memory with [`POISON_BYTE`].
+    /// The allocation count is restored to its value when the marker was made, or kept if it is lower, as older allocations
+    /// may have been dropped since. It can't tell which allocations were dropped, so if older allocations were dropped
+    /// while newer allocations are still outstanding, the count stays too high, and [`free_all`](SingleArena::free_all) panics.
+    /// 
+    /// # Safety
+    /// The marker must have been made by this chunk, and no allocations made after the marker may be used again,
+    /// including dropping their `ArenaBox`es, as they are dangling.
+    pub unsafe fn rewind(&self, marker: Marker) {
+        if self.poisoning {
+            let (current, marker) = (self.free_pointer.get(), marker.free_pointer);
+            // the freed region is between the marker and the free pointer
+            let start = std::cmp::min(current, marker);
+            std::ptr::write_bytes(start, POISON_BYTE, std::cmp::max(current, marker) as usize - start as usize);
+        }
+
+        self.free_pointer.set(marker.free_pointer);
+        // the count is never lower than the older allocations which are still outstanding
+        self.allocations.set(std::cmp::min(marker.allocations, self.allocations.get()));
+        self.unowned_allocations.set(marker.unowned_allocations);
+    }
+
+    /// Free every allocation in the chunk, by resetting the free pointer and allocation count.
+    /// 
+    /// Taking `&mut self` means that there are no outstanding `ArenaBox`es. The destructors of leaked objects are NOT run.
+    /// 
+    /// A zeroizing chunk overwrites the used region with zeros first.
+    /// Otherwise a poisoning chunk is filled with [`POISON_BYTE`].
+    pub fn reset(&mut self) {
+        self.zeroize_used_region();
+        let free_pointer = match self.direction {
+            BumpDirection::Upward => self.start_pointer,
+            // safety: the end of the memory is one byte past the allocation
+            BumpDirection::Downward => unsafe { self.start_pointer.add(self.size) }
+        };
+        self.free_pointer.set(free_pointer);
+        self.allocations.set(0);
+        self.unowned_allocations.set(0);
+
+        // zeroizing chunks are left zeroed.
+        // safety: there are no references to the chunk's memory
+        if self.poisoning && !self.zeroizing {
+            unsafe { std::ptr::write_bytes(self.start_pointer, POISON_BYTE, self.size) };
+        }
+    }
+
+    /// Free every allocation in the chunk once they have all been dropped, keeping the chunk's memory to be reused.
+    /// 
+    /// Unlike [`reset`](SingleArena::reset) this only takes `&self`, so it can be used while the chunk is borrowed,
+    /// and instead checks that the allocation count is 0. Freed memory is zeroed or poisoned as in `reset`.
+    /// 
+    /// # Panics
+    /// If the chunk has outstanding allocations, which would be overwritten by later allocations.
+    pub fn free_all(&self) {
+        assert_eq!(self.allocations.get(), 0, "free_all called with outstanding allocations");
+        if self.zeroizing {
+            let (start, len) = self.used_region();
+            // safety: there are no allocations, so nothing references the used region
+            unsafe { zeroize(start, len) };
+        }
+
+        // safety: there are no allocations, so nothing references the chunk's memory
+        if self.poisoning && !self.zeroizing {
+            unsafe { std::ptr::write_bytes(self.start_pointer, POISON_BYTE, self.size) };
+        }
+
+        let free_pointer = match self.direction {
+            BumpDirection::Upward => self.start_pointer,
+            // safety: the end of the memory is one byte past the allocation
+            BumpDirection::Downward => unsafe { self.start_pointer.add(self.size) }
+        };
+        self.free_pointer.set(free_pointer);
+    }
+
+    /// Run destructors of leaked objects in the chunk, then [`reset`](SingleArena::reset) it.
+    /// 
+    /// Each pair in `drops` is the offset of an object from the start pointer, and the drop glue for its type,
+    /// such as from [`drop_glue`](SingleArena::drop_glue). Each destructor is run exactly once, in order.
+    /// 
+    /// # Safety
+    /// Each offset must be the location of a valid object of the type that its drop glue expects,
+    /// and each object must not have been dropped already, or appear more than once.
+    pub unsafe fn reset_running_drops(&mut self, drops: &[(usize, DropGlue)]) {
+        for (offset, drop_glue) in drops {
+            debug_assert!(*offset < self.size, "Drop offset is outside of the chunk");
+            drop_glue(self.start_pointer.add(*offset));
+        }
+        self.reset();
+    }
+
+    /// Return a function which runs the destructor of a T at a pointer, for [`reset_running_drops`](SingleArena::reset_running_drops).
+    pub fn drop_glue<T>() -> DropGlue {
+        unsafe fn drop_erased<T>(ptr: *mut u8) {
+            std::ptr::drop_in_place(ptr.cast::<T>())
+        }
+        drop_erased::<T>
+    }
+
+    /// Run `f` with this chunk, and rewind the chunk afterwards, freeing everything that `f` allocated.
+    /// 
+    /// The destructors of objects that `f` leaked are NOT run.
+    /// 
+    /// Taking `&mut self` means that there are no outstanding borrows of the chunk,
+    /// and allocations made in `f` can't escape it, as they borrow the chunk for the duration of `f`.
+    /// 
+    /// ```compile_fail
+    /// use arena::{single_chunk::SingleArena, ArenaChunk};
+    /// 
+    /// let mut arena = SingleArena::new(100).unwrap();
+    /// let allocation = arena.scope(|arena| arena.allocate(5u32).unwrap());
+    /// ```
+    pub fn scope<R>(&mut self, f: impl FnOnce(&SingleArena) -> R) -> R {
+        // rewinds the chunk even if f panics
+        struct RewindGuard<'a> {
+            arena: &'a SingleArena,
+            marker: Marker
+        }
+
+        impl Drop for RewindGuard<'_> {
+            fn drop(&mut self) {
+                // safety: the marker was made by this chunk, and allocations made by f can't be used after it has returned
+                unsafe { self.arena.rewind(self.marker) }
+            }
+        }
+
+        let guard = RewindGuard { arena: self, marker: self.mark() };
+        f(guard.arena)
+    }
+
+    /// Attempt to grow the memory of an allocation by `additional` bytes, without moving it.
+    /// 
+    /// This is only possible if the allocation is the last object written to the chunk, and there is enough remaining capacity.
+    /// Returns whether the allocation was grown.
+    /// 
+    /// The extra bytes directly follow the T in memory, and are uninitialised.
+    pub fn try_grow_last<T>(&self, arena_box: &mut ArenaBox<'_, T, Self>, additional: usize) -> bool {
+        // safety: the pointer is only used for comparisons
+        let object_pointer = unsafe { ArenaBox::const_ptr(arena_box) } as usize;
+        let free_pointer = self.get_free_pointer_mut() as usize;
+
+        if size_of::<T>() == 0 || object_pointer < self.start_pointer as usize {
+            return false
+        }
+
+        // the allocation must end at the free pointer, adjacent to the free memory
+        if object_pointer + size_of::<T>() != free_pointer || additional > self.remaining_capacity() {
+            return false
+        }
+
+        // safety: there is enough remaining capacity, so the free pointer stays in the arena
+        unsafe { self.set_free_pointer(self.get_free_pointer_mut().add(additional)) }
+        true
+    }
+
+    /// Move the chunk's memory to a new allocation of `new_size` bytes, copying the used region,
+    /// and return the number of bytes that the used region moved by.
+    /// 
+    /// For a downward chunk the used region is copied to the end of the new memory.
+    /// A zeroizing chunk zeroes the old memory, and a poisoning chunk poisons the new remaining capacity.
+    /// 
+    /// # Safety
+    /// Every pointer into the chunk, such as from an `ArenaBox` that was leaked or converted with `into_raw`,
+    /// is dangling afterwards. They may be used again once they are offset by the returned delta.
+    /// 
+    /// # Panics
+    /// If `new_size` is less than the size of the chunk, or the new size with the chunk's alignment is greater than isize::MAX.
+    /// Aborts if allocating the memory fails, like `new`.
+    pub unsafe fn grow_buffer(&mut self, new_size: usize) -> isize {
+        assert!(new_size >= self.size, "grow_buffer called with a size smaller than the chunk");
+        let layout = std::alloc::Layout::from_size_align(new_size, self.align).expect("SingleArena size is greater than isize::MAX");
+        let allocation = match Self::try_intialise_chunk_aligned(new_size, self.align) {
+            Some(allocation) => allocation,
+            None => std::alloc::handle_alloc_error(layout)
+        };
+
+        let (used_start, used_len) = self.used_region();
+        let remaining = new_size - used_len;
+        let (new_used_start, new_free_pointer) = match self.direction {
+            BumpDirection::Upward => (allocation, allocation.add(used_len)),
+            BumpDirection::Downward => (allocation.add(remaining), allocation.add(remaining))
+        };
+        std::ptr::copy_nonoverlapping(used_start, new_used_start, used_len);
+        if self.poisoning {
+            let remaining_start = match self.direction {
+                BumpDirection::Upward => new_free_pointer,
+                BumpDirection::Downward => allocation
+            };
+            std::ptr::write_bytes(remaining_start, POISON_BYTE, remaining);
+        }
+
+        self.zeroize_used_region();
+        self.deallocate_arena();
+
+        self.start_pointer = allocation;
+        self.free_pointer.set(new_free_pointer);
+        self.size = new_size;
+        (new_used_start as isize).wrapping_sub(used_start as isize)
+    }
+}
+
+/// Shows how much of the chunk is used, without its memory, as the used region can contain uninitialised padding bytes.
+impl std::fmt::Debug for SingleArena {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SingleArena")
+            .field("used", &self.used_capacity())
+            .field("capacity", &self.size)
+            .field("allocations", &self.allocations.get())
+            .field("direction", &self.direction)
+            .finish()
+    }
+}
+
+// the smallest common page size, so that every page is written to by `prefault` on any platform
+const PREFAULT_PAGE_SIZE: usize = 4096;
+
+/// Overwrite `len` bytes with zeros, with volatile writes which aren't optimised away even if the memory is about to be freed.
+/// 
+/// # Safety
+/// The bytes must be valid for writes, and not referenced.
+unsafe fn zeroize(start: *mut u8, len: usize) {
+    for i in 0..len {
+        std::ptr::write_volatile(start.add(i), 0);
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
 impl ArenaChunk for SingleArena {
     unsafe fn new_unchecked(size: usize) -> Self {
-        let allocation = Self::intialise_chunk(size);
-        Self { size, start_pointer: allocation, free_pointer: Cell::new(allocation), allocations: Cell::new(0) }
+        Self::from_allocation(Self::intialise_chunk(size), size, 1)
+    }
+
+    fn alignment(&self) -> usize {
+        self.align
     }
 
-    fn allocate<T>(&self, object: T) -> Option<ArenaBox<T, Self>> {
+    fn allocate<T>(&self, object: T) -> Option<ArenaBox<'_, T, Self>> {
         let allocation_size = size_of::<T>();
 
         // handle zst
         if allocation_size == 0 {
+            if self.count_zsts {
+                // safety: a dangling pointer is valid for a zero sized type, and the box notifies the chunk when dropped
+                unsafe {
+                    std::ptr::write(NonNull::<T>::dangling().as_ptr(), object);
+                    self.adjust_allocation_count(1);
+                    return Some(ArenaBox::new(self, NonNull::dangling()))
+                }
+            }
+            // the box owns the zero sized value
+            std::mem::forget(object);
             return Some(ArenaBox::new_zero_sized())
         }
 
+        // a full chunk fails without aligning the free pointer, as most allocations do before an arena grows
+        if self.remaining_capacity() == 0 {
+            self.failed_allocations.set(self.failed_allocations.get() + 1);
+            return None
+        }
+
+        if self.direction == BumpDirection::Downward {
+            let object_pointer = self.reserve_bytes(allocation_size, align_of::<T>())?.cast::<T>();
+            // safety: the reserved memory is aligned and valid for writes, and object pointer is non-null
+            unsafe {
+                std::ptr::write(object_pointer, object);
+                self.adjust_allocation_count(1);
+                return Some(ArenaBox::new(self, NonNull::new_unchecked(object_pointer)))
+            }
+        }
+
         let offset = self.get_free_pointer_mut().align_offset(align_of::<T>());
 
         // checks that there is enough free space to allocate this object
-        if allocation_size.checked_add(offset)? <= self.remaining_capacity() {
+        if allocation_size.checked_add(offset).is_some_and(|size| size <= self.remaining_capacity()) {
             // safety: byte size is greater or equal to allocation size,
             // and there is enough remaining capacity to store the object.
             unsafe { Some(self.write_to_memory(object, allocation_size, offset)) }
         } else {
+            self.failed_allocations.set(self.failed_allocations.get() + 1);
             None
         }
     }
@@ -55,12 +637,74 @@ impl ArenaChunk for SingleArena {
         self.free_pointer.set(ptr)
     }
 
+    /// Move the free pointer in the bump direction if it stays in the free memory, between the free pointer and the
+    /// end of the chunk for upward chunks, or the start for downward chunks, inclusive.
+    fn try_set_free_pointer(&self, ptr: *mut u8) -> bool {
+        let free_pointer = self.free_pointer.get();
+        let in_free_memory = match self.direction {
+            BumpDirection::Upward => ptr >= free_pointer && ptr as usize <= self.start_pointer as usize + self.size,
+            BumpDirection::Downward => ptr <= free_pointer && ptr >= self.start_pointer
+        };
+        if !in_free_memory {
+            return false
+        }
+
+        self.free_pointer.set(ptr);
+        true
+    }
+
     fn remaining_capacity(&self) -> usize {
-        (self.start_pointer as usize + self.size) - self.free_pointer.get() as usize
+        let free_pointer = self.free_pointer.get() as usize;
+        let end_pointer = self.start_pointer as usize + self.size;
+        debug_assert!(
+            free_pointer >= self.start_pointer as usize && free_pointer <= end_pointer,
+            "Free pointer is outside of the chunk"
+        );
+
+        // saturate so a misplaced free pointer can't wrap to a huge capacity
+        match self.direction {
+            BumpDirection::Upward => end_pointer.saturating_sub(free_pointer),
+            BumpDirection::Downward => free_pointer.saturating_sub(self.start_pointer as usize)
+        }
+    }
+
+    fn reserve_bytes(&self, size: usize, align: usize) -> Option<*mut u8> {
+        let ptr = self.try_reserve_bytes(size, align);
+        if ptr.is_none() {
+            self.failed_allocations.set(self.failed_allocations.get() + 1);
+        }
+        ptr
+    }
+
+    fn capacity_for<T>(&self) -> usize {
+        let allocation_size = size_of::<T>();
+        if allocation_size == 0 {
+            return usize::MAX
+        }
+
+        let free_pointer = self.free_pointer.get() as usize;
+        match self.direction {
+            BumpDirection::Upward => {
+                let aligned = free_pointer + self.free_pointer.get().align_offset(align_of::<T>());
+                (self.start_pointer as usize + self.size).saturating_sub(aligned) / allocation_size
+            },
+            BumpDirection::Downward => {
+                let aligned = free_pointer & !(align_of::<T>() - 1);
+                aligned.saturating_sub(self.start_pointer as usize) / allocation_size
+            }
+        }
     }
 
     fn adjust_allocation_count(&self, count: isize) {
-        self.allocations.set(self.allocations.get().checked_add_signed(count).expect("Allocation count overflow (too many allocations)"))
+        self.allocations.set(adjusted_allocation_count(self.allocations.get(), count))
+    }
+
+    fn record_unowned_allocation(&self) {
+        self.unowned_allocations.set(self.unowned_allocations.get() + 1)
+    }
+
+    fn record_alignment_padding(&self, bytes: usize) {
+        self.alignment_waste.set(self.alignment_waste.get().saturating_add(bytes))
     }
 
     #[inline]
@@ -71,6 +715,16 @@ impl ArenaChunk for SingleArena {
 
 impl Drop for SingleArena {
     fn drop(&mut self) {
+        // outstanding allocations mean that a box was leaked, or a raw pointer escaped the borrow checker and is now dangling.
+        // this isn't checked while panicking, as a second panic would abort
+        debug_assert!(
+            !cfg!(feature = "leak_check") || self.allocations.get() == self.unowned_allocations.get() || std::thread::panicking(),
+            "SingleArena dropped with {} outstanding allocations",
+            self.allocations.get() - self.unowned_allocations.get()
+        );
+
+        self.zeroize_used_region();
+
         // drop means that there are no other references to the chunk, it can be safely deallocated.
         unsafe {
             self.deallocate_arena()
@@ -82,6 +736,7 @@ impl Drop for SingleArena {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ArenaBox;
 
     #[test]
     fn single_allocation() {
@@ -96,4 +751,939 @@ mod tests {
         let arena_values = unsafe { std::slice::from_raw_parts(start_ptr.cast_const(), 100) };
         assert_eq!(expected_slice.as_slice(), arena_values);
     }
+
+    #[test]
+    fn grow_last_allocation() {
+        let arena = SingleArena::new(100).unwrap();
+        let first = arena.allocate(1u8).unwrap();
+        let mut second = arena.allocate(2u8).unwrap();
+        let mut third = arena.allocate(3u8).unwrap();
+
+        // not enough capacity
+        assert!(!arena.try_grow_last(&mut third, 1000));
+
+        let free_pointer = arena.get_free_pointer_mut();
+        assert!(arena.try_grow_last(&mut third, 10));
+        assert_eq!(arena.get_free_pointer_mut(), unsafe { free_pointer.add(10) });
+
+        // not the last allocation
+        assert!(!arena.try_grow_last(&mut second, 1));
+
+        assert_eq!((*first, *second, *third), (1, 2, 3));
+    }
+
+    #[test]
+    fn allocate_with_large_struct() {
+        struct Large([u64; 512]);
+
+        let arena = SingleArena::new(6000).unwrap();
+        let allocation = arena.allocate_with(|| Large([7; 512])).unwrap();
+        assert!(allocation.0.iter().all(|i| *i == 7));
+        assert_eq!(arena.allocations.get(), 1);
+
+        // not enough capacity for a second
+        assert!(arena.allocate_with(|| Large([0; 512])).is_none());
+
+        // a panic leaves the reserved slot unused
+        let free_pointer = arena.get_free_pointer_mut();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            arena.allocate_with(|| -> u64 { panic!("constructor panicked") })
+        }));
+        assert!(result.is_err());
+        assert_eq!(arena.allocations.get(), 1);
+        assert_eq!(arena.get_free_pointer_mut(), unsafe { free_pointer.add(8) });
+    }
+
+    #[test]
+    fn new_invalid_size() {
+        assert!(SingleArena::new(0).is_none());
+        assert!(SingleArena::new(usize::MAX).is_none());
+        assert!(SingleArena::new(isize::MAX as usize + 1).is_none());
+    }
+
+    #[test]
+    fn reallocate_in_place() {
+        let arena = SingleArena::new(100).unwrap();
+        let first = arena.allocate(1u32).unwrap();
+        let second = arena.allocate(2u32).unwrap();
+        let second_pointer = unsafe { ArenaBox::const_ptr(&second) };
+
+        let slice = arena.try_reallocate(second, 4).ok().unwrap();
+        assert_eq!(*slice, [2, 0, 0, 0]);
+        assert_eq!(slice.as_ptr(), second_pointer);
+        assert_eq!(arena.remaining_capacity(), 100 - 5*4);
+        assert_eq!(arena.allocations.get(), 2);
+
+        // not enough capacity
+        let third = arena.allocate(3u32).unwrap();
+        let third = arena.try_reallocate(third, 100).err().unwrap();
+        assert_eq!((*first, *third), (1, 3));
+    }
+
+    #[test]
+    fn reallocate_copy() {
+        let arena = SingleArena::new(200).unwrap();
+        let first = arena.allocate(String::from("first")).unwrap();
+        let _second = arena.allocate(5u8).unwrap();
+
+        let slice = arena.try_reallocate(first, 3).ok().unwrap();
+        assert_eq!(*slice, [String::from("first"), String::new(), String::new()]);
+        assert_eq!(slice.as_ptr() as usize % std::mem::align_of::<String>(), 0);
+        // the old slot isn't counted
+        assert_eq!(arena.allocations.get(), 2);
+
+        drop(slice);
+        assert_eq!(arena.allocations.get(), 1);
+    }
+
+    #[test]
+    fn downward_allocation() {
+        let arena = SingleArena::new_downward(100).unwrap();
+        let start_ptr = arena.get_start_pointer_mut();
+        assert_eq!(arena.direction(), BumpDirection::Downward);
+        assert_eq!(arena.remaining_capacity(), 100);
+
+        let first = arena.allocate(1u8).unwrap();
+        assert_eq!(unsafe { ArenaBox::const_ptr(&first) }, unsafe { start_ptr.add(99) }.cast_const());
+
+        let mut allocations = Vec::new();
+        for i in 0..99_u8 {
+            allocations.push(arena.allocate(i).unwrap());
+        }
+        assert_eq!(unsafe { ArenaBox::const_ptr(allocations.last().unwrap()) }, start_ptr.cast_const());
+        assert_eq!(arena.remaining_capacity(), 0);
+        assert!(arena.allocate(0u8).is_none());
+
+        // values are unaffected by other allocations
+        assert_eq!(*first, 1);
+        assert!(allocations.iter().enumerate().all(|(i, allocation)| **allocation == i as u8));
+    }
+
+    #[test]
+    fn mark_and_rewind() {
+        let arena = SingleArena::new(100).unwrap();
+        let first = arena.allocate(1u32).unwrap();
+
+        let marker = arena.mark();
+        let remaining_capacity = arena.remaining_capacity();
+
+        for i in 0..10_u32 {
+            // leak the boxes, as they mustn't be dropped after rewinding
+            std::mem::forget(arena.allocate(i).unwrap());
+        }
+        assert_eq!(arena.allocations.get(), 11);
+
+        unsafe { arena.rewind(marker) };
+        assert_eq!(arena.remaining_capacity(), remaining_capacity);
+        assert_eq!(arena.allocations.get(), 1);
+        assert_eq!(*first, 1);
+    }
+
+    #[test]
+    fn rewind_after_older_allocation_dropped() {
+        let arena = SingleArena::new(100).unwrap();
+        let first = arena.allocate(1u32).unwrap();
+
+        let marker = arena.mark();
+        drop(first);
+        unsafe { arena.rewind(marker) };
+        assert_eq!(arena.allocations.get(), 0);
+
+        // the chunk has no outstanding allocations, so it can be freed
+        arena.free_all();
+        assert_eq!(arena.remaining_capacity(), 100);
+    }
+
+    #[test]
+    fn scope_reclaims_memory() {
+        let mut arena = SingleArena::new(100).unwrap();
+        let outer = arena.allocate_raw(1u32).unwrap();
+        let remaining_capacity = arena.remaining_capacity();
+
+        let sum = arena.scope(|arena| {
+            let allocations: Vec<_> = (0..10_u32).map(|i| arena.allocate(i).unwrap()).collect();
+            allocations.iter().map(|allocation| **allocation).sum::<u32>()
+        });
+
+        assert_eq!(sum, 45);
+        assert_eq!(arena.remaining_capacity(), remaining_capacity);
+        assert_eq!(arena.allocations.get(), 1);
+        unsafe { arena.free_raw(outer) };
+    }
+
+    #[test]
+    fn raw_allocation() {
+        let arena = SingleArena::new(100).unwrap();
+        let ptr = arena.allocate_raw(String::from("raw")).unwrap();
+        assert_eq!(arena.allocations.get(), 1);
+
+        unsafe {
+            (*ptr).push_str(" pointer");
+            assert_eq!(*ptr, "raw pointer");
+            arena.free_raw(ptr);
+        }
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn remaining_capacity_decreases() {
+        let arena = SingleArena::new(100).unwrap();
+        assert_eq!(arena.remaining_capacity(), 100);
+
+        let _byte = arena.allocate(1u8).unwrap();
+        assert_eq!(arena.remaining_capacity(), 99);
+
+        let _array = arena.allocate([0u8; 10]).unwrap();
+        assert_eq!(arena.remaining_capacity(), 89);
+
+        // u16 is aligned after the 11 bytes, so one byte of padding is used
+        let _integer = arena.allocate(2u16).unwrap();
+        assert_eq!(arena.remaining_capacity(), 86);
+    }
+
+    #[test]
+    fn capacity_for_matches_allocations() {
+        for arena in [SingleArena::new(100).unwrap(), SingleArena::new_downward(100).unwrap()] {
+            // misalign the free pointer
+            let _byte = arena.allocate(1u8).unwrap();
+            let capacity = arena.capacity_for::<u64>();
+            assert_eq!(arena.capacity_for::<()>(), usize::MAX);
+
+            let mut allocations = Vec::new();
+            while let Some(allocation) = arena.allocate(0u64) {
+                allocations.push(allocation);
+            }
+            assert_eq!(allocations.len(), capacity);
+            assert_eq!(arena.capacity_for::<u64>(), 0);
+        }
+    }
+
+    #[test]
+    fn pinned_self_referential() {
+        struct SelfReferential {
+            value: u32,
+            pointer: *const u32,
+            _pinned: std::marker::PhantomPinned
+        }
+
+        let arena = SingleArena::new(100).unwrap();
+        let _other = arena.allocate(1u8).unwrap();
+        let mut pinned = unsafe {
+            arena.allocate_pinned(SelfReferential { value: 5, pointer: std::ptr::null(), _pinned: std::marker::PhantomPinned }).unwrap()
+        };
+
+        // safety: the struct isn't moved out of the pin
+        unsafe {
+            let inner = pinned.as_mut().get_unchecked_mut();
+            inner.pointer = &inner.value;
+        }
+
+        // more allocations don't move the pinned object
+        let _others: Vec<_> = (0..10_u32).map(|i| arena.allocate(i).unwrap()).collect();
+        assert_eq!(pinned.pointer, &pinned.value as *const u32);
+        assert_eq!(unsafe { *pinned.pointer }, 5);
+    }
+
+    #[test]
+    fn parallel_columns() {
+        let arena = SingleArena::new(200).unwrap();
+        let _byte = arena.allocate(1u8).unwrap();
+
+        let mut ids = arena.allocate_columns::<u32>(10).unwrap();
+        let mut weights = arena.allocate_columns::<f32>(10).unwrap();
+        assert_eq!(ids.as_ptr() as usize % std::mem::align_of::<u32>(), 0);
+        assert_eq!(arena.allocations.get(), 3);
+
+        for i in 0..10 {
+            ids[i] = i as u32;
+            weights[i] = i as f32 * 0.5;
+        }
+
+        for i in 0..10 {
+            assert_eq!(ids[i], i as u32);
+            assert_eq!(weights[i], i as f32 * 0.5);
+        }
+
+        assert!(arena.allocate_columns::<u64>(100).is_none());
+    }
+
+    #[test]
+    fn remaining_capacity_at_end() {
+        let arena = SingleArena::new(100).unwrap();
+        unsafe { arena.set_free_pointer(arena.get_start_pointer_mut().add(100)) };
+        assert_eq!(arena.remaining_capacity(), 0);
+        assert!(arena.allocate(1u8).is_none());
+    }
+
+    #[test]
+    fn reset_running_drops_test() {
+        struct DropCounter<'a>(&'a Cell<usize>);
+        impl Drop for DropCounter<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut arena = SingleArena::new(100).unwrap();
+        let start = arena.get_start_pointer_mut() as usize;
+
+        let mut recorded = Vec::new();
+        for _i in 0..3 {
+            let mut allocation = arena.allocate(DropCounter(&drops)).unwrap();
+            let offset = unsafe { ArenaBox::mut_ptr(&mut allocation) } as usize - start;
+            recorded.push((offset, SingleArena::drop_glue::<DropCounter>()));
+            std::mem::forget(allocation);
+        }
+        assert_eq!(drops.get(), 0);
+
+        unsafe { arena.reset_running_drops(&recorded) };
+        assert_eq!(drops.get(), 3);
+        assert_eq!(arena.get_free_pointer_mut() as usize, start);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    #[cfg_attr(all(debug_assertions, feature = "leak_check"), should_panic(expected = "outstanding allocations"))]
+    fn drop_with_outstanding_allocation() {
+        // leaking is safe, so this only panics with the leak check
+        let arena = SingleArena::new(100).unwrap();
+        std::mem::forget(arena.allocate(1u32).unwrap());
+        drop(arena);
+    }
+
+    #[test]
+    fn allocate_iterators() {
+        let arena = SingleArena::new(1000).unwrap();
+
+        let exact = arena.allocate_iter_exact(0..100_u32).unwrap();
+        assert_eq!(exact.len(), 100);
+        assert_eq!(exact.iter().sum::<u32>(), 4950);
+
+        let filtered = arena.allocate_iter((0..100_u32).filter(|i| i % 2 == 0)).unwrap();
+        assert_eq!(filtered.len(), 50);
+        assert_eq!(filtered.iter().sum::<u32>(), 2450);
+        assert_eq!(arena.allocations.get(), 2);
+
+        // a panic while iterating drops the written elements
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            arena.allocate_iter_exact((0..10).map(|i| if i < 5 { String::from("item") } else { panic!("iterator panicked") }))
+        }));
+        assert!(result.is_err());
+        assert_eq!(arena.allocations.get(), 2);
+
+        assert!(arena.allocate_iter_exact((0..1000_u32).map(u64::from)).is_none());
+    }
+
+    #[test]
+    fn used_slice_reads_allocations() {
+        let arena = SingleArena::new(100).unwrap();
+        let _bytes: Vec<_> = (1..=4_u8).map(|i| arena.allocate(i).unwrap()).collect();
+        let _integer = arena.allocate(u32::from_ne_bytes([5, 6, 7, 8])).unwrap();
+
+        // safety: u8 and u32 have no padding, and the u32 is aligned after the 4 bytes, so every byte is initialised
+        let bytes: Vec<u8> = arena.used_slice().iter().map(|byte| unsafe { byte.assume_init() }).collect();
+        assert_eq!(bytes, [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn try_set_free_pointer_test() {
+        let arena = SingleArena::new(100).unwrap();
+        let _allocation = arena.allocate(1u32).unwrap();
+        let start = arena.get_start_pointer_mut();
+
+        assert!(arena.try_set_free_pointer(start.wrapping_add(40)));
+        assert_eq!(arena.get_free_pointer_mut(), start.wrapping_add(40));
+        assert!(arena.try_set_free_pointer(start.wrapping_add(100)));
+        assert_eq!(arena.remaining_capacity(), 0);
+
+        // out of range pointers, and pointers which would overwrite allocations, are rejected
+        assert!(!arena.try_set_free_pointer(start.wrapping_add(101)));
+        assert!(!arena.try_set_free_pointer(start.wrapping_add(40)));
+        assert!(!arena.try_set_free_pointer(start));
+        assert!(!arena.try_set_free_pointer(start.wrapping_sub(1)));
+        assert!(!arena.try_set_free_pointer(std::ptr::null_mut()));
+        assert_eq!(arena.get_free_pointer_mut(), start.wrapping_add(100));
+    }
+
+    #[test]
+    fn try_set_free_pointer_downward() {
+        let arena = SingleArena::new_downward(100).unwrap();
+        let _allocation = arena.allocate(1u32).unwrap();
+        let start = arena.get_start_pointer_mut();
+
+        assert!(!arena.try_set_free_pointer(start.wrapping_add(100)));
+        assert!(arena.try_set_free_pointer(start.wrapping_add(40)));
+        assert!(!arena.try_set_free_pointer(start.wrapping_add(60)));
+        assert!(!arena.try_set_free_pointer(start.wrapping_sub(1)));
+        assert!(arena.try_set_free_pointer(start));
+        assert_eq!(arena.remaining_capacity(), 0);
+    }
+
+    #[test]
+    fn allocate_aligned_test() {
+        let arena = SingleArena::new(200).unwrap();
+        let _byte = arena.allocate(1u8).unwrap();
+
+        let aligned = arena.allocate_aligned(7u32, 64).unwrap();
+        assert_eq!(*aligned, 7);
+        assert_eq!(unsafe { ArenaBox::const_ptr(&aligned) } as usize % 64, 0);
+        assert_eq!(arena.allocations.get(), 2);
+
+        assert!(arena.allocate_aligned(7u32, 48).is_none());
+        assert!(arena.allocate_aligned([0u8; 200], 1).is_none());
+    }
+
+    #[test]
+    fn zeroizing_reset() {
+        let mut arena = SingleArena::new_zeroizing(64).unwrap();
+        assert!(arena.is_zeroizing());
+        let start = arena.get_start_pointer_mut();
+
+        drop(arena.allocate(0xABAB_ABAB_ABAB_ABAB_u64).unwrap());
+        drop(arena.allocate([0xCDu8; 8]).unwrap());
+        // safety: the allocations have no padding, so every byte is initialised
+        let bytes: Vec<u8> = arena.used_slice().iter().map(|byte| unsafe { byte.assume_init() }).collect();
+        assert_eq!(bytes, [[0xAB; 8], [0xCD; 8]].concat());
+
+        arena.reset();
+        // safety: the 16 bytes were initialised by the allocations, then zeroed
+        let scrubbed = unsafe { std::slice::from_raw_parts(start, 16) };
+        assert_eq!(scrubbed, [0; 16]);
+    }
+
+    #[test]
+    fn slice_size_overflow() {
+        let arena = SingleArena::new(64).unwrap();
+
+        // wraps to a small size without the check
+        assert!(arena.allocate_columns::<u64>(usize::MAX / 8 + 2).is_none());
+        // doesn't overflow usize, but is greater than isize::MAX
+        assert!(arena.allocate_columns::<u64>(usize::MAX / 16 + 1).is_none());
+        assert!(arena.allocate_columns::<u64>(usize::MAX / 8).is_none());
+
+        let allocation = arena.allocate(1u64).unwrap();
+        assert!(arena.try_reallocate(allocation, usize::MAX / 8 + 2).is_err());
+        assert_eq!(arena.remaining_capacity(), 56);
+    }
+
+    #[test]
+    #[allow(clippy::unit_arg)]
+    fn zero_sized_types_not_counted() {
+        let mut arena = SingleArena::new(16).unwrap();
+
+        let units: Vec<_> = (0..100).map(|_i| arena.allocate(()).unwrap()).collect();
+        assert_eq!(arena.allocations.get(), 0);
+        drop(units);
+        assert_eq!(arena.allocations.get(), 0);
+        assert_eq!(arena.remaining_capacity(), 16);
+
+        // each zero sized value is dropped exactly once, by its box
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+        struct DropCounter;
+        impl Drop for DropCounter {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        drop(arena.allocate(DropCounter).unwrap());
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+
+        arena.set_count_zsts(true);
+        let units: Vec<_> = (0..100).map(|_i| arena.allocate(()).unwrap()).collect();
+        assert_eq!(arena.allocations.get(), 100);
+        assert!(ArenaBox::arena(&units[0]).is_some());
+        drop(units);
+        assert_eq!(arena.allocations.get(), 0);
+        assert_eq!(arena.remaining_capacity(), 16);
+    }
+
+    #[test]
+    fn failed_allocation_count_test() {
+        let arena = SingleArena::new(16).unwrap();
+        let allocations: Vec<_> = (0..4_u32).map(|i| arena.allocate(i).unwrap()).collect();
+
+        for _i in 0..3 {
+            assert!(arena.allocate(0u8).is_none());
+        }
+        assert!(arena.allocate_with(|| 0u64).is_none());
+        assert!(arena.allocate_columns::<u16>(2).is_none());
+        assert_eq!(arena.failed_allocation_count(), 5);
+
+        // zero sized types never fail
+        assert!(arena.allocate(()).is_some());
+        assert_eq!(arena.failed_allocation_count(), 5);
+        drop(allocations);
+
+        let downward = SingleArena::new_downward(4).unwrap();
+        assert!(downward.allocate(0u64).is_none());
+        assert_eq!(downward.failed_allocation_count(), 1);
+    }
+
+    #[test]
+    fn poisoned_memory() {
+        let mut arena = SingleArena::new(32).unwrap();
+        arena.set_poisoning(true);
+        let start = arena.get_start_pointer_mut();
+        // safety: the memory is initialised with the poison byte
+        let memory = || unsafe { std::slice::from_raw_parts(start, 32).to_vec() };
+        assert_eq!(memory(), [POISON_BYTE; 32]);
+
+        let marker = arena.mark();
+        std::mem::forget(arena.allocate(0u64).unwrap());
+        assert_eq!(memory()[..8], [0; 8]);
+
+        unsafe { arena.rewind(marker) };
+        assert_eq!(memory(), [POISON_BYTE; 32]);
+    }
+
+    #[test]
+    fn free_all_test() {
+        let arena = SingleArena::new(16).unwrap();
+        let allocations: Vec<_> = (0..4_u32).map(|i| arena.allocate(i).unwrap()).collect();
+        assert_eq!(arena.remaining_capacity(), 0);
+
+        drop(allocations);
+        arena.free_all();
+        assert_eq!(arena.remaining_capacity(), 16);
+        assert_eq!(arena.get_free_pointer_mut(), arena.get_start_pointer_mut());
+    }
+
+    #[test]
+    #[should_panic(expected = "free_all called with outstanding allocations")]
+    fn free_all_with_live_box() {
+        let arena = SingleArena::new(16).unwrap();
+        let _allocation = arena.allocate(1u32).unwrap();
+        arena.free_all();
+    }
+
+    #[test]
+    fn mixed_alignment_and_destructors() {
+        #[repr(align(32))]
+        struct Aligned(u8);
+
+        struct DropCounter<'a>(&'a Cell<usize>, u16);
+        impl Drop for DropCounter<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let arena = SingleArena::new(1024).unwrap();
+        let mut allocations = Vec::new();
+        for i in 0..8_u16 {
+            let byte = arena.allocate(i as u8).unwrap();
+            let aligned = arena.allocate(Aligned(i as u8)).unwrap();
+            let counter = arena.allocate(DropCounter(&drops, i)).unwrap();
+
+            assert_eq!(unsafe { ArenaBox::const_ptr(&aligned) } as usize % 32, 0);
+            assert_eq!(unsafe { ArenaBox::const_ptr(&counter) } as usize % align_of::<DropCounter>(), 0);
+            allocations.push((byte, aligned, counter));
+        }
+
+        for (i, (byte, aligned, counter)) in allocations.iter().enumerate() {
+            assert_eq!((**byte as usize, aligned.0 as usize, counter.1 as usize), (i, i, i));
+        }
+        assert_eq!(drops.get(), 0);
+
+        drop(allocations);
+        assert_eq!(drops.get(), 8);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn uninit_slice() {
+        let arena = SingleArena::new(256).unwrap();
+        let mut slice = arena.allocate_uninit_slice::<u32>(50).unwrap();
+        assert_eq!(arena.allocations.get(), 1);
+
+        for (i, element) in slice.iter_mut().enumerate() {
+            element.write(i as u32);
+        }
+        // safety: every element has been written
+        let slice = unsafe { ArenaBox::assume_init(slice) };
+        assert_eq!(slice.iter().sum::<u32>(), 1225);
+
+        assert!(arena.allocate_uninit_slice::<u32>(50).is_none());
+        drop(slice);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn debug_used_region() {
+        let arena = SingleArena::new(64).unwrap();
+        let _byte = arena.allocate(1u8).unwrap();
+        let _integer = arena.allocate(2u32).unwrap();
+
+        // the padding before the u32 is counted as used
+        assert_eq!(format!("{:?}", arena), "SingleArena { used: 8, capacity: 64, allocations: 2, direction: Upward }");
+    }
+
+    #[test]
+    fn used_capacity_with_padding() {
+        let arena = SingleArena::new(64).unwrap();
+        let downward = SingleArena::new_downward(64).unwrap();
+
+        for chunk in [&arena, &downward] {
+            let _byte = chunk.allocate(1u8).unwrap();
+            let _integer = chunk.allocate(2u64).unwrap();
+            let _short = chunk.allocate(3u16).unwrap();
+            assert!(chunk.used_capacity() > 11);
+            assert_eq!(chunk.used_capacity() + chunk.remaining_capacity(), chunk.size());
+        }
+        assert_eq!(arena.used_capacity(), 18);
+        assert_eq!(downward.used_capacity(), 18);
+    }
+
+    #[test]
+    fn allocate_closures() {
+        let calls = Cell::new(0);
+        let dropped = Cell::new(false);
+        struct DropFlag<'a>(&'a Cell<bool>);
+        impl Drop for DropFlag<'_> {
+            fn drop(&mut self) {
+                self.0.set(true);
+            }
+        }
+
+        let arena = SingleArena::new(256).unwrap();
+        let mut total = 0;
+        let (flag, calls_ref) = (DropFlag(&dropped), &calls);
+        let mut callbacks = vec![
+            arena.allocate_fn(|| calls.set(calls.get() + 1)).unwrap(),
+            arena.allocate_fn(|| calls.set(calls.get() + 10)).unwrap(),
+            arena.allocate_fn(move || { let _flag = &flag; calls_ref.set(calls_ref.get() + 100) }).unwrap()
+        ];
+
+        for _i in 0..3 {
+            for callback in callbacks.iter_mut() {
+                (**callback)();
+            }
+        }
+        assert_eq!(calls.get(), 333);
+        assert_eq!(arena.allocations.get(), 3);
+
+        // a closure capturing by mutable reference
+        let mut increment = arena.allocate_fn(|| total += 1).unwrap();
+        (*increment)();
+        (*increment)();
+        drop(increment);
+        assert_eq!(total, 2);
+
+        assert!(!dropped.get());
+        drop(callbacks);
+        assert!(dropped.get());
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Allocation count overflow (too many allocations)")]
+    fn allocation_count_overflow() {
+        let arena = SingleArena::new(16).unwrap();
+        arena.allocations.set(usize::MAX);
+        let _allocation = arena.allocate(0u8);
+    }
+
+    #[test]
+    #[should_panic(expected = "Allocation count underflow (an allocation was freed more than once)")]
+    fn allocation_count_underflow() {
+        let arena = SingleArena::new(16).unwrap();
+        arena.adjust_allocation_count(-1);
+    }
+
+    #[test]
+    fn allocate_vec_moves_elements() {
+        let arena = SingleArena::new(1024).unwrap();
+        let vec: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+        let string_pointers: Vec<*const u8> = vec.iter().map(|string| string.as_ptr()).collect();
+
+        let slice = arena.allocate_vec(vec).unwrap();
+        assert_eq!(slice.len(), 10);
+        for (i, string) in slice.iter().enumerate() {
+            assert_eq!(*string, i.to_string());
+            // the strings were moved, not cloned
+            assert_eq!(string.as_ptr(), string_pointers[i]);
+        }
+        assert_eq!(arena.allocations.get(), 1);
+
+        assert!(arena.allocate_vec(Vec::<String>::new()).unwrap().is_empty());
+        assert_eq!(arena.allocate_vec(vec![(); 5]).unwrap().len(), 5);
+        assert_eq!(arena.allocations.get(), 1);
+    }
+
+    #[test]
+    fn allocate_vec_drops_elements_once() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let arena = SingleArena::new(1024).unwrap();
+        let slice = arena.allocate_vec(vec![Rc::clone(&counter); 3]).unwrap();
+        assert_eq!(Rc::strong_count(&counter), 4);
+        drop(slice);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn used_slice_covers_used_bytes() {
+        let arena = SingleArena::new(100).unwrap();
+        let _byte = arena.allocate(1u8).unwrap();
+        // the u32 is aligned after 3 bytes of padding
+        let _word = arena.allocate(0x04030201_u32).unwrap();
+
+        let mut snapshot = Vec::new();
+        snapshot.extend_from_slice(arena.used_slice());
+        assert_eq!(snapshot.len(), arena.used_capacity());
+
+        // safety: the u32 initialised its bytes
+        let last_bytes: Vec<u8> = snapshot[snapshot.len() - 4..].iter().map(|byte| unsafe { byte.assume_init() }).collect();
+        assert_eq!(u32::from_ne_bytes(last_bytes.try_into().unwrap()), 0x04030201);
+    }
+
+    #[test]
+    fn reserve_then_allocate_unchecked() {
+        let arena = SingleArena::new(100).unwrap();
+        assert!(!arena.reserve(101));
+        assert!(arena.reserve(64));
+
+        // the chunk is aligned for u32s, so 16 u32s use 64 bytes without padding
+        let allocations: Vec<_> = (0..16_u32).map(|i| unsafe { arena.allocate_unchecked(i) }).collect();
+        assert_eq!(arena.used_capacity(), 64);
+        assert!(allocations.iter().enumerate().all(|(i, allocation)| **allocation == i as u32));
+
+        assert!(arena.reserve(36));
+        assert!(!arena.reserve(37));
+        // allocate_unchecked can't be used with a downward chunk
+        let downward = SingleArena::new_downward(100).unwrap();
+        assert!(!downward.reserve(1));
+    }
+
+    #[test]
+    fn alloc_mut_without_box() {
+        let mut arena = SingleArena::new(100).unwrap();
+        let value = arena.alloc_mut(5u64).unwrap();
+        let other = arena.alloc_mut([1u8; 4]).unwrap();
+        *value += 10;
+        other[3] = 9;
+
+        assert_eq!(*value, 15);
+        assert_eq!(*other, [1, 1, 1, 9]);
+        assert_eq!(arena.allocations.get(), 2);
+        assert!(arena.alloc_mut([0u8; 100]).is_none());
+
+        // the objects aren't dropped, and their memory is freed by resetting the chunk
+        arena.reset();
+        assert_eq!(arena.allocations.get(), 0);
+        assert!(arena.alloc_mut([0u8; 100]).is_some());
+    }
+
+    #[test]
+    fn drop_after_alloc_mut() {
+        // the allocation isn't a leak, so dropping the chunk doesn't panic, even with the leak check
+        let arena = SingleArena::new(8).unwrap();
+        *arena.alloc_mut(1u8).unwrap() += 1;
+        drop(arena.allocate(2u8).unwrap());
+        drop(arena);
+
+        let fixed = crate::fixed_chunk::FixedArena::<u32>::with_capacity(2).unwrap();
+        fixed.alloc_mut(3u32).unwrap();
+        drop(fixed);
+    }
+
+    #[test]
+    fn allocate_try_with_rolls_back() {
+        let arena = SingleArena::new(100).unwrap();
+        let parse = |text: &str| arena.allocate_try_with(|| text.parse::<u64>()).unwrap();
+
+        let number = parse("42").unwrap();
+        assert_eq!(*number, 42);
+        let used = arena.used_capacity();
+
+        assert!(parse("not a number").is_err());
+        assert_eq!(arena.used_capacity(), used);
+        assert_eq!(arena.allocations.get(), 1);
+
+        assert_eq!(*parse("7").unwrap(), 7);
+        assert_eq!(arena.used_capacity(), used + 8);
+        assert!(arena.allocate_try_with(|| Ok::<_, ()>([0u8; 100])).is_none());
+    }
+
+    #[test]
+    fn allocate_slice_with_index() {
+        let arena = SingleArena::new(1024).unwrap();
+        let squares = arena.allocate_slice_with(100, |i| (i * i) as u32).unwrap();
+        assert_eq!(squares.len(), 100);
+        assert!(squares.iter().enumerate().all(|(i, square)| *square as usize == i * i));
+        assert!(arena.allocate_slice_with(1000, |i| i as u32).is_none());
+    }
+
+    #[test]
+    fn allocate_slice_with_panic_drops_written() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let arena = SingleArena::new(1024).unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            arena.allocate_slice_with(10, |i| {
+                assert!(i < 5, "generator failed");
+                Rc::clone(&counter)
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(Rc::strong_count(&counter), 1);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn over_aligned_chunk() {
+        let arena = SingleArena::new_aligned(100, 4096).unwrap();
+        assert_eq!(arena.alignment(), 4096);
+        assert_eq!(arena.get_start_pointer_mut() as usize % 4096, 0);
+
+        // no padding is needed for the first allocation
+        let aligned = arena.allocate_aligned(5u8, 4096).unwrap();
+        assert_eq!(arena.used_capacity(), 1);
+        drop(aligned);
+
+        assert!(SingleArena::new_aligned(100, 3).is_none());
+        assert!(SingleArena::new_aligned(0, 8).is_none());
+        assert_eq!(SingleArena::new(100).unwrap().alignment(), 1);
+        // the chunk is deallocated with its alignment when dropped
+    }
+
+    #[test]
+    fn allocate_raw_bytes_layouts() {
+        use std::alloc::Layout;
+
+        let arena = SingleArena::new(1024).unwrap();
+        let layouts = [(1, 1), (8, 8), (3, 2), (64, 64), (5, 1), (16, 16), (2, 4)];
+
+        let mut regions = Vec::new();
+        for (size, align) in layouts {
+            let ptr = arena.allocate_raw_bytes(Layout::from_size_align(size, align).unwrap()).unwrap();
+            assert_eq!(ptr.as_ptr() as usize % align, 0);
+            regions.push((ptr.as_ptr() as usize, size));
+        }
+
+        for (i, &(start, size)) in regions.iter().enumerate() {
+            assert!(regions[i + 1..].iter().all(|&(other, _)| other >= start + size));
+        }
+        assert!(arena.allocate_raw_bytes(Layout::from_size_align(1024, 1).unwrap()).is_none());
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    fn prefault_keeps_allocations() {
+        // prefaulting is best-effort, so this only checks that allocations made before and after aren't corrupted
+        for arena in [SingleArena::new(1 << 20).unwrap(), SingleArena::new_downward(1 << 20).unwrap()] {
+            let before = arena.allocate([7u8; 5000]).unwrap();
+            arena.prefault();
+            assert!(before.iter().all(|&byte| byte == 7));
+
+            let after: Vec<_> = (0..1000_u64).map(|i| arena.allocate(i * 3).unwrap()).collect();
+            assert!(after.iter().enumerate().all(|(i, value)| **value == i as u64 * 3));
+            assert!(before.iter().all(|&byte| byte == 7));
+        }
+    }
+
+    #[test]
+    fn prefault_keeps_poison() {
+        let mut arena = SingleArena::new(3 * PREFAULT_PAGE_SIZE).unwrap();
+        arena.set_poisoning(true);
+        let _allocation = arena.allocate([1u8; 10]).unwrap();
+        arena.prefault();
+
+        let free = arena.get_free_pointer_mut();
+        // safety: the remaining capacity is initialised with the poison byte
+        let remaining = unsafe { std::slice::from_raw_parts(free, arena.remaining_capacity()) };
+        assert!(remaining.iter().all(|&byte| byte == POISON_BYTE));
+    }
+
+    #[test]
+    fn alignment_waste_of_interleaved_allocations() {
+        for arena in [SingleArena::new(256).unwrap(), SingleArena::new_downward(256).unwrap()] {
+            let mut allocations = Vec::new();
+            for i in 0..4_u8 {
+                allocations.push((arena.allocate(i).unwrap(), arena.allocate(u64::from(i)).unwrap()));
+            }
+            // the start and end of the chunk are 8 byte aligned, so each u64 after a u8 skips 7 bytes
+            assert_eq!(arena.alignment_waste(), 4 * 7);
+
+            // sorting by decreasing alignment has no waste
+            let sorted = SingleArena::new(256).unwrap();
+            let _wide: Vec<_> = (0..4_u64).map(|i| sorted.allocate(i).unwrap()).collect();
+            let _narrow: Vec<_> = (0..4_u8).map(|i| sorted.allocate(i).unwrap()).collect();
+            assert_eq!(sorted.alignment_waste(), 0);
+        }
+    }
+
+    #[test]
+    fn grow_buffer_preserves_used_region() {
+        for mut arena in [SingleArena::new(16).unwrap(), SingleArena::new_downward(16).unwrap()] {
+            let values = arena.allocate([1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]).unwrap();
+            let (ptr, _) = ArenaBox::into_raw(values);
+            assert!(arena.allocate(0u64).is_none());
+
+            let delta = unsafe { arena.grow_buffer(1024) };
+            assert_eq!(arena.size(), 1024);
+            assert_eq!(arena.remaining_capacity(), 1024 - 12);
+
+            // the raw pointer is fixed up by the delta, and the box is rebuilt to free the allocation
+            let moved = NonNull::new(ptr.as_ptr().cast::<u8>().wrapping_offset(delta).cast::<[u8; 12]>()).unwrap();
+            let values = unsafe { ArenaBox::from_raw(moved, Some(&arena)) };
+            assert_eq!(*values, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+            assert_eq!(*arena.allocate(3u64).unwrap(), 3);
+            drop(values);
+            assert_eq!(arena.allocations.get(), 0);
+        }
+    }
+
+    #[test]
+    fn full_chunk_fails() {
+        for arena in [SingleArena::new(8).unwrap(), SingleArena::new_downward(8).unwrap()] {
+            let full = arena.allocate(1u64).unwrap();
+            assert_eq!(arena.remaining_capacity(), 0);
+
+            assert!(arena.allocate(2u8).is_none());
+            assert!(arena.allocate(3u64).is_none());
+            assert_eq!(arena.failed_allocation_count(), 2);
+            // zero sized types don't need capacity
+            assert!(arena.allocate(()).is_some());
+            assert_eq!(*full, 1);
+        }
+    }
+
+    #[test]
+    fn clone_bytes_is_independent() {
+        let arena = SingleArena::new(64).unwrap();
+        let values = arena.allocate([1u32, 2, 3, 4]).unwrap();
+        // the chunk can be cloned while its allocations are borrowed
+        let clone = arena.clone_bytes();
+        assert_eq!(clone.size(), 64);
+        assert_eq!(clone.used_capacity(), 16);
+        assert_eq!(clone.allocations.get(), 0);
+
+        let cloned = clone.get_start_pointer_mut().cast::<[u32; 4]>();
+        // safety: the clone has the same bytes at the same offset, and no boxes own its memory
+        unsafe {
+            assert_eq!(*cloned, [1, 2, 3, 4]);
+            (*cloned)[0] = 10;
+            assert_eq!(*values, [1, 2, 3, 4]);
+            assert_eq!((*cloned)[0], 10);
+        }
+        drop(values);
+        assert_eq!(arena.allocations.get(), 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Allocation is outside of the chunk")]
+    fn allocate_unchecked_without_capacity() {
+        let arena = SingleArena::new(8).unwrap();
+        let _full = arena.allocate(1u32).unwrap();
+        // safety: this isn't safe, but the debug assertion panics before the object is written
+        let _ = unsafe { arena.allocate_unchecked(2u64) };
+    }
 }
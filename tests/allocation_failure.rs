@@ -0,0 +1,40 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+
+use arena::{ArenaBuilder, single_chunk::SingleArena};
+
+// allocations of this size fail, so only the chunks of the arenas below are affected
+const FAILING_SIZE: usize = 12345;
+
+struct FailingAllocator;
+
+unsafe impl GlobalAlloc for FailingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() == FAILING_SIZE {
+            return std::ptr::null_mut()
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: FailingAllocator = FailingAllocator;
+
+#[test]
+fn try_allocate_returns_object() {
+    let arena = ArenaBuilder::new().chunk_size(FAILING_SIZE).build().unwrap();
+    assert_eq!(arena.try_allocate(String::from("not allocated")).err().unwrap(), "not allocated");
+    assert_eq!(arena.chunk_count(), 0);
+
+    // a larger allocation needs a larger chunk, which can be allocated
+    let allocation = arena.try_allocate([1u8; FAILING_SIZE + 1]).ok().unwrap();
+    assert_eq!(allocation[FAILING_SIZE], 1);
+    assert_eq!(arena.chunk_count(), 1);
+    drop(allocation);
+
+    assert!(SingleArena::try_new(FAILING_SIZE).is_none());
+    assert!(SingleArena::try_new(FAILING_SIZE - 1).is_some());
+}
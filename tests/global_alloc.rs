@@ -0,0 +1,39 @@
+use std::alloc::{GlobalAlloc, Layout};
+
+use arena::ArenaGlobal;
+
+#[global_allocator]
+static GLOBAL: ArenaGlobal<{ 8 * 1024 * 1024 }> = ArenaGlobal::new();
+
+#[test]
+fn allocations_come_from_arena() {
+    let used = GLOBAL.used();
+
+    let boxed = Box::new(5u64);
+    let mut vec: Vec<u32> = (0..100).collect();
+    vec.push(100);
+    let string = String::from("global arena");
+
+    assert!(GLOBAL.used() > used + 100 * 4);
+    assert_eq!(*boxed, 5);
+    assert_eq!(vec.iter().sum::<u32>(), 5050);
+    assert_eq!(string, "global arena");
+
+    let start = &GLOBAL as *const _ as usize;
+    let end = start + std::mem::size_of_val(&GLOBAL);
+    let pointer = &*boxed as *const u64 as usize;
+    assert!(pointer >= start && pointer < end);
+    assert_eq!(pointer % std::mem::align_of::<u64>(), 0);
+}
+
+#[test]
+fn aligned_and_exhausted_allocations() {
+    unsafe {
+        let pointer = GLOBAL.alloc(Layout::from_size_align(64, 64).unwrap());
+        assert!(!pointer.is_null());
+        assert_eq!(pointer as usize % 64, 0);
+
+        let too_large = Layout::from_size_align(GLOBAL.remaining_capacity() + 1, 1).unwrap();
+        assert!(GLOBAL.alloc(too_large).is_null());
+    }
+}